@@ -1,4 +1,9 @@
-use avail_orbit_raas_blueprint_lib::jobs::{modify_rollup_metadata, restart_rollup, update_bridge};
+use avail_orbit_raas_blueprint_lib::jobs::{
+    deposit_eth, export_logs, export_node_config, force_batch_post, get_avail_stats, get_batch_lag,
+    get_contract_addresses, get_logs, get_resource_usage, modify_rollup_metadata, restart_rollup,
+    self_test, set_batch_max_items, set_block_gas_limit, set_min_base_fee, set_speed_limit,
+    stop_rollup, update_bridge,
+};
 use blueprint_sdk::build;
 use blueprint_sdk::tangle::blueprint;
 use std::path::Path;
@@ -22,7 +27,26 @@ fn main() {
         name: "experiment",
         master_manager_revision: "Latest",
         manager: { Evm = "HelloBlueprint" },
-        jobs: [modify_rollup_metadata, restart_rollup, update_bridge]
+        jobs: [
+            modify_rollup_metadata,
+            restart_rollup,
+            update_bridge,
+            set_batch_max_items,
+            get_contract_addresses,
+            get_avail_stats,
+            set_min_base_fee,
+            export_node_config,
+            get_batch_lag,
+            get_resource_usage,
+            set_speed_limit,
+            set_block_gas_limit,
+            force_batch_post,
+            export_logs,
+            self_test,
+            deposit_eth,
+            stop_rollup,
+            get_logs
+        ]
     };
 
     match blueprint {
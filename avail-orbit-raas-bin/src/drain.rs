@@ -0,0 +1,69 @@
+//! Maintenance draining for Tangle jobs
+//!
+//! Applied once on the job router, inside [`TangleLayer`](blueprint_sdk::tangle::layers::TangleLayer)
+//! so the result still carries the call's metadata. While the operator is draining, every job
+//! is answered with a failed summary instead of running.
+
+use avail_orbit_raas_blueprint_lib::{OrbitContext, jobs};
+use blueprint_sdk::{IntoJobResult, JobCall, JobResult};
+use futures::future::{BoxFuture, FutureExt};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Layer turning jobs away while the operator is draining
+#[derive(Clone)]
+pub struct DrainLayer {
+    ctx: OrbitContext,
+}
+
+impl DrainLayer {
+    /// Check the draining flag of `ctx` on every job call
+    pub fn new(ctx: OrbitContext) -> Self {
+        Self { ctx }
+    }
+}
+
+impl<S> Layer<S> for DrainLayer {
+    type Service = Drain<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Drain {
+            inner,
+            ctx: self.ctx.clone(),
+        }
+    }
+}
+
+/// Service answering job calls with a failed result while draining
+#[derive(Clone)]
+pub struct Drain<S> {
+    inner: S,
+    ctx: OrbitContext,
+}
+
+impl<S> Service<JobCall> for Drain<S>
+where
+    S: Service<JobCall, Response = Option<JobResult>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+{
+    type Response = Option<JobResult>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Option<JobResult>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, call: JobCall) -> Self::Future {
+        if self.ctx.is_draining() {
+            let job = format!("job {:?}", call.job_id());
+            let result = jobs::draining_result(&self.ctx, &job)
+                .ok()
+                .and_then(IntoJobResult::into_job_result);
+            futures::future::ready(Ok(result)).boxed()
+        } else {
+            self.inner.call(call).boxed()
+        }
+    }
+}
@@ -1,7 +1,27 @@
-use avail_orbit_raas_blueprint_lib::config::{AvailOrbitConfig, OperatorConfig};
-use avail_orbit_raas_blueprint_lib::types::RollupMetadata;
+mod drain;
+mod rate_limit;
+
+use avail_orbit_raas_blueprint_lib::cleanup::{OrphanCleanup, cleanup_orphaned_deployments};
+use avail_orbit_raas_blueprint_lib::config::{
+    AvailOrbitConfig, DeploymentConfig, EnvVars, OperatorConfig, SettlementLayer,
+};
+use avail_orbit_raas_blueprint_lib::diagnostics::{DiagnosticsReport, collect_diagnostics};
+use avail_orbit_raas_blueprint_lib::events::{DeploymentEvent, EventKind, EventLog};
+use avail_orbit_raas_blueprint_lib::metrics::{AvailStats, ServiceMetrics};
+use avail_orbit_raas_blueprint_lib::persistence::StatusPersister;
+use avail_orbit_raas_blueprint_lib::rpc::probe_rpc_ready;
+use avail_orbit_raas_blueprint_lib::selftest::{SelfTestReport, run_self_test};
+use avail_orbit_raas_blueprint_lib::startup::StartupReport;
+use avail_orbit_raas_blueprint_lib::types::{
+    BatchLag, ChainId, ChainInfo, ConfigReload, ContainerRestart, DeployedAddresses,
+    DeploymentPhase, DeploymentStep, RollupMetadata, parse_labels,
+};
 use avail_orbit_raas_blueprint_lib::{DeploymentStatus, OrbitContext, deployment, jobs, util};
-use axum::{Extension, Json, Router as AxumRouter, routing::get};
+use axum::extract::Query;
+use axum::http::{StatusCode, header};
+use axum::response::sse::{self, KeepAlive, Sse};
+use axum::{Extension, Json, Router as AxumRouter, routing::get, routing::post};
+use blueprint_sdk::Router;
 use blueprint_sdk::contexts::tangle::TangleClientContext;
 use blueprint_sdk::crypto::sp_core::SpSr25519;
 use blueprint_sdk::crypto::tangle_pair_signer::TanglePairSigner;
@@ -13,12 +33,18 @@ use blueprint_sdk::tangle::consumer::TangleConsumer;
 use blueprint_sdk::tangle::filters::MatchesServiceId;
 use blueprint_sdk::tangle::layers::TangleLayer;
 use blueprint_sdk::tangle::producer::TangleProducer;
-use blueprint_sdk::{Job, Router};
 use dotenv::dotenv;
+use drain::DrainLayer;
+use futures::Stream;
+use rate_limit::RateLimitLayer;
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::Infallible;
 use std::env;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
+use tokio::sync::broadcast::error::RecvError;
 use tower::filter::FilterLayer;
 use tower_http::trace::TraceLayer;
 use tracing::level_filters::LevelFilter;
@@ -27,10 +53,43 @@ use tracing::{error, info, warn};
 const MODIFY_ROLLUP_METADATA_JOB_ID: u32 = 1;
 const RESTART_ROLLUP_JOB_ID: u32 = 2;
 const UPDATE_BRIDGE_JOB_ID: u32 = 3;
+const SET_BATCH_MAX_ITEMS_JOB_ID: u32 = 4;
+const GET_CONTRACT_ADDRESSES_JOB_ID: u32 = 5;
+const GET_AVAIL_STATS_JOB_ID: u32 = 6;
+const SET_MIN_BASE_FEE_JOB_ID: u32 = 7;
+const EXPORT_NODE_CONFIG_JOB_ID: u32 = 8;
+const GET_BATCH_LAG_JOB_ID: u32 = 9;
+const GET_RESOURCE_USAGE_JOB_ID: u32 = 10;
+const SET_SPEED_LIMIT_JOB_ID: u32 = 11;
+const SET_BLOCK_GAS_LIMIT_JOB_ID: u32 = 12;
+const FORCE_BATCH_POST_JOB_ID: u32 = 13;
+const EXPORT_LOGS_JOB_ID: u32 = 14;
+const SELF_TEST_JOB_ID: u32 = 15;
+const DEPOSIT_ETH_JOB_ID: u32 = 16;
+const STOP_ROLLUP_JOB_ID: u32 = 17;
+const GET_LOGS_JOB_ID: u32 = 18;
+
+/// Default requests per second for cheap, in-memory endpoints
+const DEFAULT_RATE_LIMIT_RPS: u32 = 20;
+/// Default requests per second for endpoints that call Docker or an RPC node
+const DEFAULT_EXPENSIVE_RATE_LIMIT_RPS: u32 = 2;
+/// Default attempts at connecting to Tangle on startup
+const DEFAULT_TANGLE_CONNECT_ATTEMPTS: u32 = 10;
+/// Default delay before the first Tangle reconnect, doubled after each failure
+const DEFAULT_TANGLE_CONNECT_DELAY_SECS: u64 = 2;
 
 /// HTTP server state
 struct AppState {
     deployment_status: Arc<Mutex<DeploymentStatus>>,
+    orbit_context: OrbitContext,
+    rate_limits: RateLimits,
+    metrics: Arc<ServiceMetrics>,
+}
+
+/// Per-route-group HTTP request budgets, in requests per second
+struct RateLimits {
+    default_rps: u32,
+    expensive_rps: u32,
 }
 
 #[tokio::main]
@@ -45,78 +104,282 @@ async fn main() -> Result<(), blueprint_sdk::Error> {
     check_prerequisites().await;
 
     // Load operator configuration from environment variables
-    let operator_config = load_operator_config()?;
+    let mut operator_config = load_operator_config(&EnvVars::default())?;
+
+    // Self-tests probe the Tangle node the runner actually uses
+    let env = BlueprintEnvironment::load()?;
+    operator_config.deployment.tangle_rpc_url = env.http_rpc_endpoint.to_string();
 
     // Initialize the orbit context with the operator config
-    let orbit_ctx = OrbitContext::new(operator_config.clone());
+    let orbit_ctx =
+        OrbitContext::new(operator_config.clone()).map_err(blueprint_sdk::Error::Other)?;
     let deployment_status = orbit_ctx.status.clone();
 
+    // A deployment still in progress in the persisted status was cut short by a crash, and
+    // a finished one may still be running
+    let mut previous_deployment = None;
+    let interrupted = match orbit_ctx.store.load().await {
+        Ok(Some(mut previous)) if previous.mark_interrupted() => {
+            warn!(
+                "Previous deployment was interrupted during {:?}",
+                previous.interrupted
+            );
+            if let Err(e) = orbit_ctx.store.save(previous.clone()).await {
+                warn!("Failed to record interrupted deployment: {}", e);
+            }
+            orbit_ctx.events.record(EventKind::DeploymentInterrupted {
+                step: previous.interrupted,
+            });
+            Some(previous)
+        }
+        Ok(previous) => {
+            previous_deployment = previous
+                .filter(|previous| previous.deployed || previous.phase == DeploymentPhase::Stopped);
+            None
+        }
+        Err(e) => {
+            warn!("Ignoring unreadable persisted status: {}", e);
+            None
+        }
+    };
+    let resume = operator_config.deployment.resume_interrupted;
+
+    // Periodically persist the status so it survives restarts
+    let persister = StatusPersister::new(deployment_status.clone(), orbit_ctx.store.clone());
+    persister.spawn(operator_config.deployment.status_flush_interval());
+
     // Load rollup metadata from environment variables
-    let rollup_metadata = load_rollup_metadata()?;
+    let rollup_metadata = load_rollup_metadata(&EnvVars::default())?;
 
     // Create the deployment configuration by combining operator config (private) with metadata (public)
-    let config = AvailOrbitConfig::new(operator_config, rollup_metadata.clone());
-
-    // Deploy the rollup in a separate task to avoid blocking the main thread
-    let ctx_clone = orbit_ctx.clone();
-    tokio::spawn(async move {
-        info!("Deploying Avail Orbit rollup...");
-        match deployment::deploy_rollup(config).await {
-            Ok(status) => {
-                info!("Rollup deployed successfully!");
-                // Update the shared status
-                *ctx_clone.status.lock().await = status;
+    let config = AvailOrbitConfig::new(operator_config, rollup_metadata.clone())
+        .map_err(blueprint_sdk::Error::Other)?;
+
+    // Summarize what is about to be deployed, and stop before deploying if any check failed
+    let report = StartupReport::new(&config);
+    info!("Startup configuration:\n{}", report);
+    report.check().map_err(blueprint_sdk::Error::Other)?;
+
+    // An interrupted deployment is resumed by deploying again, which reuses completed work,
+    // unless the operator asked to keep it for inspection and cleanup
+    let skip_deploy = match interrupted {
+        Some(previous) if !resume => {
+            warn!(
+                "Not resuming the interrupted deployment (RESUME_INTERRUPTED_DEPLOY=false); \
+                 clean up with POST /cleanup/orphans or docker compose down, then restart"
+            );
+            *orbit_ctx.status.lock().await = previous;
+            true
+        }
+        Some(_) => {
+            info!("Resuming interrupted deployment");
+            false
+        }
+        None => match previous_deployment {
+            // A rollup stopped on request stays stopped until it is restarted
+            Some(previous) if previous.phase == DeploymentPhase::Stopped => {
+                info!("Previous deployment was stopped; not starting it until restarted");
+                *orbit_ctx.status.lock().await = previous;
+                true
             }
-            Err(e) => {
-                error!("Failed to deploy rollup: {}", e);
-                // Continue with job setup anyway - the user can deploy later via API or job
+            // Take over the rollup deployed before the restart rather than deploying again
+            Some(mut previous) => {
+                match deployment::reattach(config.get_deployment_config(), &previous).await {
+                    Ok(()) => {
+                        info!(
+                            "Reattached to {} running containers of the previous deployment",
+                            previous.container_ids.len()
+                        );
+                        previous
+                            .push_log("Reattached to running containers after restart".to_string());
+                        *orbit_ctx.status.lock().await = previous;
+                        true
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Redeploying, previous deployment can't be reattached: {}",
+                            e
+                        );
+                        false
+                    }
+                }
             }
-        }
-    });
-
-    // Start the HTTP server in a separate task
-    let app_state = AppState {
-        deployment_status: deployment_status.clone(),
+            None => false,
+        },
     };
 
-    tokio::spawn(start_http_server(app_state));
+    // Counters of the service's own activity, served at GET /metrics
+    let metrics = Arc::new(ServiceMetrics::default());
+
+    if !skip_deploy {
+        // Deploy the rollup in a separate task to avoid blocking the main thread
+        let ctx_clone = orbit_ctx.clone();
+        let deploy_persister = persister.clone();
+        let deploy_metrics = metrics.clone();
+        tokio::spawn(async move {
+            info!("Deploying Avail Orbit rollup...");
+            ctx_clone.events.record(EventKind::DeploymentStarted);
+            deploy_metrics.record_deployment_attempt();
+
+            // Mirror progress into the shared status so /status and /logs show it live, and
+            // record each newly completed step in the event log
+            let (progress, mut snapshots) = tokio::sync::mpsc::channel(16);
+            let live_status = ctx_clone.status.clone();
+            let events = ctx_clone.events.clone();
+            let forwarder = tokio::spawn(async move {
+                let mut recorded: Vec<DeploymentStep> = Vec::new();
+                while let Some(snapshot) = snapshots.recv().await {
+                    for step in &snapshot.completed_steps {
+                        if !recorded.contains(step) {
+                            recorded.push(*step);
+                            events.record(EventKind::StepCompleted { step: *step });
+                        }
+                    }
+                    *live_status.lock().await = snapshot;
+                }
+            });
+
+            let result = deployment::deploy_rollup_with_progress(config, progress).await;
+            // The sender is gone, so this finishes once queued snapshots are applied and can't
+            // overwrite the final status below
+            let _ = forwarder.await;
+            match result {
+                Ok(status) => {
+                    info!("Rollup deployed successfully!");
+                    // Update the shared status
+                    *ctx_clone.status.lock().await = status;
+                    ctx_clone.events.record(EventKind::DeploymentSucceeded);
+                }
+                Err(e) => {
+                    error!("Failed to deploy rollup: {}", e);
+                    ctx_clone
+                        .events
+                        .record(EventKind::DeploymentFailed { error: e.clone() });
+                    let mut status = ctx_clone.status.lock().await;
+                    deploy_metrics.record_deployment_failure(status.current_step());
+                    status.fail(e);
+                    // Continue with job setup anyway - the user can deploy later via API or job
+                }
+            }
+            deploy_persister.request_flush();
+        });
+    }
+
+    // Start the HTTP server in a separate task, unless disabled. The deployment status it
+    // would serve is still owned by the orbit context, so jobs are unaffected either way.
+    let http_enabled = env::var("ENABLE_HTTP")
+        .map(|enable| enable.to_lowercase() != "false")
+        .unwrap_or(true);
+    let http_server = if http_enabled {
+        let app_state = AppState {
+            deployment_status: deployment_status.clone(),
+            orbit_context: orbit_ctx.clone(),
+            rate_limits: load_rate_limits()?,
+            metrics: metrics.clone(),
+        };
+        Some(tokio::spawn(start_http_server(app_state)))
+    } else {
+        info!("HTTP server disabled by ENABLE_HTTP=false");
+        None
+    };
 
     // Set up Tangle integration for job processing
-    let env = BlueprintEnvironment::load()?;
-    let sr25519_signer = env.keystore().first_local::<SpSr25519>()?;
+    let sr25519_signer = match env.keystore().first_local::<SpSr25519>() {
+        Ok(signer) => signer,
+        Err(e) => {
+            error!(
+                "No sr25519 key found in the keystore at {} ({}). Tangle jobs need an sr25519 \
+                 operator key: generate or import one into that keystore (e.g. with the \
+                 `cargo tangle` CLI) and restart the service.",
+                env.keystore_uri, e
+            );
+            let Some(http_server) = http_server else {
+                return Err(blueprint_sdk::Error::Other(
+                    "No sr25519 key in the keystore and the HTTP server is disabled".to_string(),
+                ));
+            };
+
+            warn!("Running in HTTP-only mode; Tangle jobs are disabled");
+            if let Err(e) = http_server.await {
+                error!("HTTP server task failed: {}", e);
+            }
+            flush_status(&persister, &orbit_ctx.events).await;
+            return Ok(());
+        }
+    };
     let sr25519_pair = env.keystore().get_secret::<SpSr25519>(&sr25519_signer)?;
     let st25519_signer = TanglePairSigner::new(sr25519_pair.0);
 
-    let tangle_client = env.tangle_client().await?;
-    let tangle_producer =
-        TangleProducer::finalized_blocks(tangle_client.rpc_client.clone()).await?;
+    // The Tangle RPC may be briefly unavailable, e.g. during a coordinated restart
+    let (attempts, delay) = load_tangle_retry()?;
+    let (tangle_client, tangle_producer) = util::retry_with_backoff(
+        attempts,
+        delay,
+        |_, _| true,
+        |attempt| {
+            let env = &env;
+            async move {
+                info!("Connecting to Tangle (attempt {}/{})", attempt, attempts);
+                let client = env
+                    .tangle_client()
+                    .await
+                    .map_err(|e| format!("Failed to connect to Tangle: {}", e))?;
+                let producer = TangleProducer::finalized_blocks(client.rpc_client.clone())
+                    .await
+                    .map_err(|e| format!("Failed to subscribe to Tangle blocks: {}", e))?;
+                Ok((client, producer))
+            }
+        },
+    )
+    .await
+    .map_err(blueprint_sdk::Error::Other)?;
     let tangle_consumer = TangleConsumer::new(tangle_client.rpc_client.clone(), st25519_signer);
 
     let tangle_config = TangleConfig::default();
 
     let service_id = env.protocol_settings.tangle()?.service_id.unwrap();
+    let events = orbit_ctx.events.clone();
     let result = BlueprintRunner::builder(tangle_config, env)
         .router(
             // Define job routes for state-changing operations only
             // These job functions accept only public data, with no private keys
             Router::new()
-                .route(
-                    MODIFY_ROLLUP_METADATA_JOB_ID,
-                    jobs::modify_rollup_metadata.layer(TangleLayer),
-                )
-                .route(
-                    RESTART_ROLLUP_JOB_ID,
-                    jobs::restart_rollup.layer(TangleLayer),
-                )
-                .route(UPDATE_BRIDGE_JOB_ID, jobs::update_bridge.layer(TangleLayer))
+                .route(MODIFY_ROLLUP_METADATA_JOB_ID, jobs::modify_rollup_metadata)
+                .route(RESTART_ROLLUP_JOB_ID, jobs::restart_rollup)
+                .route(UPDATE_BRIDGE_JOB_ID, jobs::update_bridge)
+                .route(SET_BATCH_MAX_ITEMS_JOB_ID, jobs::set_batch_max_items)
+                .route(GET_CONTRACT_ADDRESSES_JOB_ID, jobs::get_contract_addresses)
+                .route(GET_AVAIL_STATS_JOB_ID, jobs::get_avail_stats)
+                .route(SET_MIN_BASE_FEE_JOB_ID, jobs::set_min_base_fee)
+                .route(EXPORT_NODE_CONFIG_JOB_ID, jobs::export_node_config)
+                .route(GET_BATCH_LAG_JOB_ID, jobs::get_batch_lag)
+                .route(GET_RESOURCE_USAGE_JOB_ID, jobs::get_resource_usage)
+                .route(SET_SPEED_LIMIT_JOB_ID, jobs::set_speed_limit)
+                .route(SET_BLOCK_GAS_LIMIT_JOB_ID, jobs::set_block_gas_limit)
+                .route(FORCE_BATCH_POST_JOB_ID, jobs::force_batch_post)
+                .route(EXPORT_LOGS_JOB_ID, jobs::export_logs)
+                .route(SELF_TEST_JOB_ID, jobs::self_test)
+                .route(DEPOSIT_ETH_JOB_ID, jobs::deposit_eth)
+                .route(STOP_ROLLUP_JOB_ID, jobs::stop_rollup)
+                .route(GET_LOGS_JOB_ID, jobs::get_logs)
+                // Jobs answer with a failed result while draining, so only this service's
+                // callers are turned away, and they learn why. The drain layer sits inside
+                // TangleLayer so that result still reaches the caller.
+                .layer(DrainLayer::new(orbit_ctx.clone()))
+                .layer(TangleLayer)
                 .layer(FilterLayer::new(MatchesServiceId(service_id)))
                 // Use our orbit context (which contains the operator config securely)
                 .with_context(orbit_ctx),
         )
         .producer(tangle_producer)
         .consumer(tangle_consumer)
-        .with_shutdown_handler(async {
-            info!("Shutting down Avail Orbit RaaS...");
+        .with_shutdown_handler({
+            let persister = persister.clone();
+            let events = events.clone();
+            async move {
+                info!("Shutting down Avail Orbit RaaS...");
+                flush_status(&persister, &events).await;
+            }
         })
         .run()
         .await;
@@ -124,64 +387,127 @@ async fn main() -> Result<(), blueprint_sdk::Error> {
     if let Err(e) = result {
         error!("Runner failed! {e:?}");
     }
+    flush_status(&persister, &events).await;
 
     Ok(())
 }
 
-/// Load operator configuration from environment variables
-fn load_operator_config() -> Result<OperatorConfig, blueprint_sdk::Error> {
+/// Write the deployment status and any queued events one last time before exiting
+async fn flush_status(persister: &StatusPersister, events: &Arc<EventLog>) {
+    if let Err(e) = persister.flush().await {
+        error!("Failed to persist deployment status: {}", e);
+    }
+    let events = events.clone();
+    if let Err(e) = tokio::task::spawn_blocking(move || events.flush()).await {
+        error!("Failed to persist deployment events: {}", e);
+    }
+}
+
+/// Load operator configuration from `vars`
+fn load_operator_config(vars: &EnvVars) -> Result<OperatorConfig, blueprint_sdk::Error> {
+    let deployment = DeploymentConfig::from_vars(vars).map_err(blueprint_sdk::Error::Other)?;
+    // On a local fork, unset keys are filled with funded anvil accounts
+    let local_fork = deployment.settlement_layer == SettlementLayer::LocalFork;
+    let private_key = |name: &str| match vars.var(name) {
+        Ok(key) => Ok(key),
+        Err(_) if local_fork => Ok(String::new()),
+        Err(_) => Err(blueprint_sdk::Error::Other(format!("{} not set", name))),
+    };
+
     let operator_config = OperatorConfig {
-        deployer_private_key: env::var("DEPLOYER_PRIVATE_KEY")
-            .map_err(|_| blueprint_sdk::Error::Other("DEPLOYER_PRIVATE_KEY not set".to_string()))?,
-        batch_poster_private_key: env::var("BATCH_POSTER_PRIVATE_KEY").map_err(|_| {
-            blueprint_sdk::Error::Other("BATCH_POSTER_PRIVATE_KEY not set".to_string())
-        })?,
-        validator_private_key: env::var("VALIDATOR_PRIVATE_KEY").map_err(|_| {
-            blueprint_sdk::Error::Other("VALIDATOR_PRIVATE_KEY not set".to_string())
-        })?,
-        avail_addr_seed: env::var("AVAIL_ADDR_SEED")
+        deployer_private_key: private_key("DEPLOYER_PRIVATE_KEY")?,
+        batch_poster_private_key: private_key("BATCH_POSTER_PRIVATE_KEY")?,
+        validator_private_key: private_key("VALIDATOR_PRIVATE_KEY")?,
+        avail_addr_seed: vars
+            .var("AVAIL_ADDR_SEED")
             .map_err(|_| blueprint_sdk::Error::Other("AVAIL_ADDR_SEED not set".to_string()))?,
-        fallback_s3_access_key: env::var("FALLBACKS3_ACCESS_KEY").ok(),
-        fallback_s3_secret_key: env::var("FALLBACKS3_SECRET_KEY").ok(),
-        fallback_s3_region: env::var("FALLBACKS3_REGION").ok(),
-        fallback_s3_object_prefix: env::var("FALLBACKS3_OBJECT_PREFIX").ok(),
-        fallback_s3_bucket: env::var("FALLBACKS3_BUCKET").ok(),
+        fallback_s3_access_key: vars.var("FALLBACKS3_ACCESS_KEY").ok(),
+        fallback_s3_secret_key: vars.var("FALLBACKS3_SECRET_KEY").ok(),
+        fallback_s3_region: vars.var("FALLBACKS3_REGION").ok(),
+        fallback_s3_object_prefix: vars.var("FALLBACKS3_OBJECT_PREFIX").ok(),
+        fallback_s3_bucket: vars.var("FALLBACKS3_BUCKET").ok(),
+        deployment,
     };
 
     info!("Loaded operator configuration from environment");
     Ok(operator_config)
 }
 
-/// Load rollup metadata from environment variables
-fn load_rollup_metadata() -> Result<RollupMetadata, blueprint_sdk::Error> {
-    // Parse chain ID from env var with a fallback value
-    let chain_id = env::var("ROLLUP_CHAIN_ID")
-        .map(|id| id.parse::<u64>().unwrap_or(412346))
-        .unwrap_or(412346);
+/// Load rollup metadata from `vars`
+fn load_rollup_metadata(vars: &EnvVars) -> Result<RollupMetadata, blueprint_sdk::Error> {
+    // Only an unset chain ID falls back to the default; a typo must not deploy that chain
+    let chain_id = match vars.var("ROLLUP_CHAIN_ID") {
+        Ok(id) => id
+            .parse::<ChainId>()
+            .map_err(|e| blueprint_sdk::Error::Other(format!("Invalid ROLLUP_CHAIN_ID: {}", e)))?,
+        Err(_) => ChainId::default(),
+    };
 
     // Parse S3 fallback flag
-    let fallback_s3_enable = env::var("FALLBACKS3_ENABLE")
+    let fallback_s3_enable = vars
+        .var("FALLBACKS3_ENABLE")
         .map(|enable| enable.to_lowercase() == "true")
         .unwrap_or(false);
 
     let rollup_metadata = RollupMetadata {
-        name: env::var("ROLLUP_NAME").unwrap_or_else(|_| "Avail Orbit Rollup".to_string()),
+        name: vars
+            .var("ROLLUP_NAME")
+            .unwrap_or_else(|_| "Avail Orbit Rollup".to_string()),
         chain_id,
-        avail_app_id: env::var("AVAIL_APP_ID")
+        avail_app_id: vars
+            .var("AVAIL_APP_ID")
             .map_err(|_| blueprint_sdk::Error::Other("AVAIL_APP_ID not set".to_string()))?,
-        parent_chain_rpc: env::var("PARENT_CHAIN_RPC")
+        parent_chain_rpc: vars
+            .var("PARENT_CHAIN_RPC")
             .map_err(|_| blueprint_sdk::Error::Other("PARENT_CHAIN_RPC not set".to_string()))?,
         fallback_s3_enable,
-        local_rpc_endpoint: env::var("ROLLUP_LOCAL_RPC")
+        local_rpc_endpoint: vars
+            .var("ROLLUP_LOCAL_RPC")
             .unwrap_or_else(|_| "http://localhost:8449".to_string()),
-        explorer_url: env::var("ROLLUP_EXPLORER_URL")
+        explorer_url: vars
+            .var("ROLLUP_EXPLORER_URL")
             .unwrap_or_else(|_| "http://localhost:4000".to_string()),
+        labels: parse_labels(&vars.var("ROLLUP_LABELS").unwrap_or_default())
+            .map_err(|e| blueprint_sdk::Error::Other(format!("Invalid ROLLUP_LABELS: {}", e)))?,
     };
 
     info!("Loaded rollup metadata from environment");
     Ok(rollup_metadata)
 }
 
+/// Load HTTP rate limits from environment variables
+fn load_rate_limits() -> Result<RateLimits, blueprint_sdk::Error> {
+    let parse = |name: &str, default: u32| match env::var(name) {
+        Ok(value) => value
+            .parse::<u32>()
+            .map_err(|e| blueprint_sdk::Error::Other(format!("Invalid {}: {}", name, e))),
+        Err(_) => Ok(default),
+    };
+
+    Ok(RateLimits {
+        default_rps: parse("RATE_LIMIT_RPS", DEFAULT_RATE_LIMIT_RPS)?,
+        expensive_rps: parse("RATE_LIMIT_EXPENSIVE_RPS", DEFAULT_EXPENSIVE_RATE_LIMIT_RPS)?,
+    })
+}
+
+/// Load the Tangle connection retry budget: attempts and initial delay
+fn load_tangle_retry() -> Result<(u32, Duration), blueprint_sdk::Error> {
+    let attempts = match env::var("TANGLE_CONNECT_ATTEMPTS") {
+        Ok(value) => value.parse::<u32>().map_err(|e| {
+            blueprint_sdk::Error::Other(format!("Invalid TANGLE_CONNECT_ATTEMPTS: {}", e))
+        })?,
+        Err(_) => DEFAULT_TANGLE_CONNECT_ATTEMPTS,
+    };
+    let delay_secs = match env::var("TANGLE_CONNECT_DELAY_SECS") {
+        Ok(value) => value.parse::<u64>().map_err(|e| {
+            blueprint_sdk::Error::Other(format!("Invalid TANGLE_CONNECT_DELAY_SECS: {}", e))
+        })?,
+        Err(_) => DEFAULT_TANGLE_CONNECT_DELAY_SECS,
+    };
+
+    Ok((attempts.max(1), Duration::from_secs(delay_secs)))
+}
+
 /// Check prerequisites for running the service
 async fn check_prerequisites() {
     // Check for Docker
@@ -190,6 +516,9 @@ async fn check_prerequisites() {
         Ok(false) => warn!("Docker is installed but not responding correctly"),
         Err(e) => error!("Docker check failed: {}", e),
     }
+    if let Err(e) = util::check_docker_daemon_access().await {
+        error!("{}", e);
+    }
 
     // Check for Docker Compose
     match util::check_docker_compose_available().await {
@@ -205,23 +534,63 @@ async fn check_prerequisites() {
         Err(e) => error!("NPM check failed: {}", e),
     }
 
-    // Check for Yarn
-    match util::check_yarn_available().await {
-        Ok(true) => info!("Yarn is available"),
-        Ok(false) => warn!("Yarn is installed but not responding correctly"),
-        Err(e) => error!("Yarn check failed: {}", e),
+    // Check for the configured package manager, which runs the deploy scripts
+    let manager = DeploymentConfig::from_env()
+        .map(|deployment| deployment.package_manager)
+        .unwrap_or_default();
+    match util::check_package_manager_available(manager).await {
+        Ok(true) => info!("{} is available", manager),
+        Ok(false) => warn!("{} is installed but not responding correctly", manager),
+        Err(e) => error!("{} check failed: {}", manager, e),
+    }
+
+    // Check for cast, used to tune chain parameters
+    match util::check_cast_available().await {
+        Ok(true) => info!("cast is available"),
+        Ok(false) => warn!("cast is installed but not responding correctly"),
+        Err(e) => error!("cast check failed: {}", e),
     }
 }
 
 // Start an HTTP server for querying rollup status
 async fn start_http_server(state: AppState) {
+    // Endpoints that call out to Docker or an RPC node get a tighter budget
+    let expensive_routes = AxumRouter::new()
+        .route("/health/rpc", get(rpc_health_check))
+        .route("/diagnostics", get(get_diagnostics))
+        .route("/selftest", get(self_test))
+        .route("/avail/stats", get(get_avail_stats))
+        .route("/health/batch-lag", get(get_batch_lag))
+        .route("/prewarm", post(prewarm))
+        .route("/reload-config", post(reload_config))
+        .route("/cleanup/orphans", post(cleanup_orphans))
+        .route("/sequencer/restart", post(restart_sequencer))
+        .route_layer(RateLimitLayer::per_second(state.rate_limits.expensive_rps));
+
     let app = AxumRouter::new()
         // Endpoints for querying rollup state (read-only operations)
         .route("/status", get(get_rollup_status))
         .route("/logs", get(get_deployment_logs))
+        .route("/events/history", get(get_event_history))
+        .route("/events/stream", get(stream_events))
+        .route(
+            "/commands/deploy_rollup/output",
+            get(get_deploy_rollup_output),
+        )
+        .route("/config/node/export", get(get_node_config_export))
+        .route("/config/chain", get(get_chain_info))
+        .route("/contracts", get(get_contracts))
+        .route("/metrics", get(get_metrics))
         .route("/health", get(health_check))
+        .route("/ready", get(readiness_check))
+        .route("/drain", post(drain))
+        .route("/undrain", post(undrain))
+        .route_layer(RateLimitLayer::per_second(state.rate_limits.default_rps))
+        .merge(expensive_routes)
         .layer(TraceLayer::new_for_http())
-        .layer(Extension(state.deployment_status));
+        .layer(Extension(state.deployment_status))
+        .layer(Extension(state.orbit_context))
+        .layer(Extension(state.metrics));
 
     let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
@@ -247,10 +616,304 @@ async fn get_deployment_logs(
     Json(status.lock().await.logs.clone())
 }
 
+async fn get_deploy_rollup_output(
+    Extension(status): Extension<Arc<Mutex<DeploymentStatus>>>,
+) -> Json<BTreeMap<DeploymentStep, String>> {
+    Json(status.lock().await.step_outputs.clone())
+}
+
+async fn get_node_config_export(
+    Extension(ctx): Extension<OrbitContext>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    deployment::export_node_config(&ctx)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))
+}
+
+async fn get_chain_info(
+    Extension(ctx): Extension<OrbitContext>,
+) -> Result<Json<ChainInfo>, (StatusCode, String)> {
+    deployment::chain_info(&ctx)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))
+}
+
+/// Contract addresses of the deployed rollup, or 404 with the reason if there are none
+async fn get_contracts(
+    Extension(ctx): Extension<OrbitContext>,
+) -> Result<Json<DeployedAddresses>, (StatusCode, Json<serde_json::Value>)> {
+    deployment::contract_addresses(&ctx)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": e })),
+            )
+        })
+}
+
+async fn get_diagnostics(Extension(ctx): Extension<OrbitContext>) -> Json<DiagnosticsReport> {
+    Json(collect_diagnostics(&ctx).await)
+}
+
+/// Run the control plane self-test, failing with 503 if any check failed
+async fn self_test(Extension(ctx): Extension<OrbitContext>) -> (StatusCode, Json<SelfTestReport>) {
+    let report = run_self_test(&ctx).await;
+    let code = if report.passed {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(report))
+}
+
+async fn get_avail_stats(
+    Extension(ctx): Extension<OrbitContext>,
+) -> Result<Json<AvailStats>, (StatusCode, String)> {
+    deployment::avail_stats(&ctx)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))
+}
+
+async fn prewarm(
+    Extension(ctx): Extension<OrbitContext>,
+) -> Result<Json<Vec<String>>, (StatusCode, String)> {
+    let logs = deployment::prewarm(&ctx.deployment_config().await)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    for line in &logs {
+        ctx.log(line).await;
+    }
+    Ok(Json(logs))
+}
+
+/// Re-read the `.env` file and environment, then swap in the new config if it is valid
+async fn reload_config(
+    Extension(ctx): Extension<OrbitContext>,
+) -> Result<Json<ConfigReload>, (StatusCode, String)> {
+    // Values from `.env` must win over those loaded at startup, or edits would be ignored.
+    // They are read into an overlay, since the environment of a running multi-threaded
+    // process can't safely be modified.
+    let overlay = match dotenvy::from_filename_iter(".env") {
+        Ok(entries) => entries
+            .collect::<Result<BTreeMap<String, String>, _>>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid .env file: {}", e)))?,
+        Err(e) => {
+            warn!("No .env file reloaded: {}", e);
+            BTreeMap::new()
+        }
+    };
+    let vars = EnvVars::with_overlay(overlay);
+
+    let bad_request = |e: blueprint_sdk::Error| (StatusCode::BAD_REQUEST, e.to_string());
+    let operator_config = load_operator_config(&vars).map_err(bad_request)?;
+    let metadata = load_rollup_metadata(&vars).map_err(bad_request)?;
+
+    let reload = deployment::reload_config(&ctx, operator_config, metadata)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    info!(
+        "Reloaded configuration (changed: {:?}, restart required: {})",
+        reload.changed, reload.restart_required
+    );
+    Ok(Json(reload))
+}
+
+/// Query parameters for `POST /sequencer/restart`
+#[derive(serde::Deserialize)]
+struct RestartParams {
+    /// Seconds the sequencer gets to stop before it is killed; defaults to
+    /// `COMPOSE_DOWN_TIMEOUT_SECS`
+    timeout_secs: Option<u64>,
+}
+
+/// Restart the sequencer container in place and report its new state
+async fn restart_sequencer(
+    Extension(ctx): Extension<OrbitContext>,
+    Query(params): Query<RestartParams>,
+) -> Result<Json<ContainerRestart>, (StatusCode, String)> {
+    let timeout_secs = match params.timeout_secs {
+        Some(secs) => secs,
+        None => ctx.deployment_config().await.compose_down_timeout_secs,
+    };
+    deployment::restart_sequencer(&ctx, Duration::from_secs(timeout_secs))
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))
+}
+
+/// Query parameters for `POST /cleanup/orphans`
+#[derive(serde::Deserialize)]
+struct CleanupParams {
+    /// Only report what would be removed; pass `dry_run=false` to delete
+    #[serde(default = "default_dry_run")]
+    dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+async fn cleanup_orphans(
+    Extension(ctx): Extension<OrbitContext>,
+    Query(params): Query<CleanupParams>,
+) -> Result<Json<OrphanCleanup>, (StatusCode, String)> {
+    cleanup_orphaned_deployments(&ctx, params.dry_run)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+/// Query parameters for `GET /events/history`
+#[derive(serde::Deserialize)]
+struct EventHistoryParams {
+    /// Return only events with a greater sequence number
+    #[serde(default)]
+    since: u64,
+}
+
+/// Recorded deployment events after `since`, in sequence order
+async fn get_event_history(
+    Extension(ctx): Extension<OrbitContext>,
+    Query(params): Query<EventHistoryParams>,
+) -> Json<Vec<DeploymentEvent>> {
+    Json(ctx.events.since(params.since))
+}
+
+/// Recorded deployment events after `since`, then each new event as it is recorded
+///
+/// Served as server-sent events whose ID is the sequence number. A client that falls
+/// behind the live channel is sent what it missed from the history, so nothing is skipped.
+async fn stream_events(
+    Extension(ctx): Extension<OrbitContext>,
+    Query(params): Query<EventHistoryParams>,
+) -> Sse<impl Stream<Item = Result<sse::Event, Infallible>>> {
+    let (history, receiver) = ctx.events.subscribe_since(params.since);
+    let stream = futures::stream::unfold(
+        (ctx, VecDeque::from(history), receiver, params.since),
+        |(ctx, mut pending, mut receiver, mut last_seq)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    // Resubscribing after a lag can repeat events already sent
+                    if event.seq <= last_seq {
+                        continue;
+                    }
+                    last_seq = event.seq;
+                    let message = sse::Event::default()
+                        .id(event.seq.to_string())
+                        .json_data(&event)
+                        .unwrap_or_else(|e| sse::Event::default().comment(e.to_string()));
+                    return Some((Ok(message), (ctx, pending, receiver, last_seq)));
+                }
+                match receiver.recv().await {
+                    Ok(event) => pending.push_back(event),
+                    Err(RecvError::Lagged(_)) => {
+                        let (missed, resubscribed) = ctx.events.subscribe_since(last_seq);
+                        pending.extend(missed);
+                        receiver = resubscribed;
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Stop accepting new jobs, letting running ones finish
+async fn drain(Extension(ctx): Extension<OrbitContext>) -> Json<serde_json::Value> {
+    ctx.set_draining(true);
+    info!("Draining: new jobs are rejected until POST /undrain");
+    Json(serde_json::json!({ "draining": true }))
+}
+
+/// Resume accepting new jobs
+async fn undrain(Extension(ctx): Extension<OrbitContext>) -> Json<serde_json::Value> {
+    ctx.set_draining(false);
+    info!("Drain cleared: accepting new jobs");
+    Json(serde_json::json!({ "draining": false }))
+}
+
+/// The service's own metrics in Prometheus text format
+async fn get_metrics(
+    Extension(metrics): Extension<Arc<ServiceMetrics>>,
+    Extension(status): Extension<Arc<Mutex<DeploymentStatus>>>,
+) -> ([(header::HeaderName, &'static str); 1], String) {
+    let body = metrics.render(&*status.lock().await);
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
 async fn health_check() -> &'static str {
     "OK"
 }
 
+async fn get_batch_lag(
+    Extension(ctx): Extension<OrbitContext>,
+) -> Result<Json<BatchLag>, (StatusCode, String)> {
+    deployment::batch_lag(&ctx)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))
+}
+
+async fn rpc_health_check(
+    Extension(ctx): Extension<OrbitContext>,
+    Extension(status): Extension<Arc<Mutex<DeploymentStatus>>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let chain_id = probe_rollup_rpc(&ctx, &status).await?;
+    Ok(Json(serde_json::json!({ "chain_id": chain_id })))
+}
+
+/// Readiness for orchestrators: the rollup is deployed and its RPC responds
+///
+/// Unlike `/health`, which only shows the process is alive, this fails during the
+/// deployment window and whenever the node stops serving.
+async fn readiness_check(
+    Extension(ctx): Extension<OrbitContext>,
+    Extension(status): Extension<Arc<Mutex<DeploymentStatus>>>,
+) -> Result<&'static str, (StatusCode, String)> {
+    if !status.lock().await.deployed {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Rollup not deployed".to_string(),
+        ));
+    }
+    probe_rollup_rpc(&ctx, &status).await?;
+    Ok("READY")
+}
+
+/// Query the rollup's RPC once, returning its chain ID
+async fn probe_rollup_rpc(
+    ctx: &OrbitContext,
+    status: &Mutex<DeploymentStatus>,
+) -> Result<u64, (StatusCode, String)> {
+    let rpc_url = status
+        .lock()
+        .await
+        .metadata
+        .as_ref()
+        .map(|metadata| metadata.local_rpc_endpoint.clone())
+        .ok_or((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Rollup not deployed".to_string(),
+        ))?;
+
+    let client = ctx
+        .deployment_config()
+        .await
+        .proxy
+        .http_client()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    probe_rpc_ready(&client, &rpc_url, 1, Duration::ZERO)
+        .await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))
+}
+
 // Logging setup
 fn setup_log() {
     use tracing_subscriber::util::SubscriberInitExt;
@@ -0,0 +1,119 @@
+//! Rate limiting for the HTTP API
+//!
+//! A fixed one-second window limiter applied as a tower layer. Each route group, or a
+//! single route, gets its own [`RateLimitLayer`] and so its own budget. Requests over the
+//! budget are rejected with `429 Too Many Requests` rather than queued.
+
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use futures::future::{BoxFuture, FutureExt};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+
+/// Shared request budget for the routes a [`RateLimitLayer`] wraps
+#[derive(Clone)]
+struct RateLimiter {
+    limit: u32,
+    window: Arc<Mutex<Window>>,
+}
+
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+impl RateLimiter {
+    /// Allow up to `limit` requests per second
+    fn per_second(limit: u32) -> Self {
+        Self {
+            limit,
+            window: Arc::new(Mutex::new(Window {
+                started: Instant::now(),
+                count: 0,
+            })),
+        }
+    }
+
+    /// Take one request from the budget, returning false if it is exhausted
+    fn try_acquire(&self) -> bool {
+        let mut window = self
+            .window
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if window.started.elapsed() >= Duration::from_secs(1) {
+            window.started = Instant::now();
+            window.count = 0;
+        }
+
+        if window.count < self.limit {
+            window.count += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Layer limiting the requests per second to the routes it is applied to
+///
+/// Every service built by one layer shares its budget, so applying it with `route_layer`
+/// limits the whole route group together.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: RateLimiter,
+}
+
+impl RateLimitLayer {
+    /// Allow up to `limit` requests per second
+    pub fn per_second(limit: u32) -> Self {
+        Self {
+            limiter: RateLimiter::per_second(limit),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+/// Service rejecting requests once its layer's budget is exhausted
+#[derive(Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    limiter: RateLimiter,
+}
+
+impl<S> Service<Request> for RateLimit<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible>,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        if self.limiter.try_acquire() {
+            self.inner.call(request).boxed()
+        } else {
+            let response = (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded").into_response();
+            futures::future::ready(Ok(response)).boxed()
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use avail_orbit_raas_blueprint_lib::config::{AvailOrbitConfig, OperatorConfig};
+use avail_orbit_raas_blueprint_lib::config::{AvailOrbitConfig, DeploymentConfig, OperatorConfig};
 use avail_orbit_raas_blueprint_lib::deployment;
-use avail_orbit_raas_blueprint_lib::types::RollupMetadata;
+use avail_orbit_raas_blueprint_lib::types::{
+    ChainId, DeploymentStep, RollupMetadata, parse_labels,
+};
 use avail_orbit_raas_blueprint_lib::util;
 use std::env;
 use std::process::exit;
@@ -45,16 +47,32 @@ async fn main() -> Result<(), String> {
     );
 
     // Create deployment config
-    let config = AvailOrbitConfig::new(operator_config, rollup_metadata.clone());
+    let config = AvailOrbitConfig::new(operator_config, rollup_metadata.clone()).map_err(|e| {
+        error!("Invalid rollup configuration: {}", e);
+        e
+    })?;
 
     // Execute deployment with detailed logging
     info!("Starting rollup deployment...");
+    for (i, step) in DeploymentStep::all().iter().enumerate() {
+        info!("  {}. {}", i + 1, step);
+    }
+
     match deployment::deploy_rollup(config).await {
         Ok(status) => {
             info!("✅ Deployment successful!");
-            info!("Deployment status: is_deployed={}", status.deployed);
+            info!(
+                "Deployment status: is_deployed={}, is_healthy={}",
+                status.deployed, status.healthy
+            );
             info!("Container IDs: {:?}", status.container_ids);
 
+            info!("Deployment steps:");
+            for step in DeploymentStep::all() {
+                let done = status.completed_steps.contains(step);
+                info!("  [{}] {}", if done { "x" } else { " " }, step);
+            }
+
             if let Some(metadata) = status.metadata {
                 info!(
                     "Deployed rollup: {} (chain ID: {})",
@@ -114,6 +132,15 @@ fn load_operator_config() -> Result<OperatorConfig, String> {
         }
     };
 
+    let deployment = DeploymentConfig::from_env().map_err(|e| {
+        error!("Invalid deployment configuration: {}", e);
+        e
+    })?;
+    debug!(
+        "Deploying with {} settlement layer",
+        deployment.settlement_layer
+    );
+
     let operator_config = OperatorConfig {
         deployer_private_key: deployer_key,
         batch_poster_private_key: batch_poster_key,
@@ -124,6 +151,7 @@ fn load_operator_config() -> Result<OperatorConfig, String> {
         fallback_s3_region: env::var("FALLBACKS3_REGION").ok(),
         fallback_s3_object_prefix: env::var("FALLBACKS3_OBJECT_PREFIX").ok(),
         fallback_s3_bucket: env::var("FALLBACKS3_BUCKET").ok(),
+        deployment,
     };
 
     debug!("Operator configuration loaded successfully");
@@ -136,19 +164,22 @@ fn load_rollup_metadata() -> Result<RollupMetadata, String> {
 
     // Parse chain ID with fallback value and detailed error handling
     let chain_id = match env::var("ROLLUP_CHAIN_ID") {
-        Ok(id_str) => match id_str.parse::<u64>() {
+        Ok(id_str) => match id_str.parse::<ChainId>() {
             Ok(id) => {
                 debug!("Parsed chain ID: {}", id);
                 id
             }
             Err(e) => {
                 warn!("Invalid ROLLUP_CHAIN_ID, using default: {}", e);
-                412346
+                ChainId::DEFAULT_ORBIT
             }
         },
         Err(_) => {
-            debug!("ROLLUP_CHAIN_ID not set, using default: 412346");
-            412346
+            debug!(
+                "ROLLUP_CHAIN_ID not set, using default: {}",
+                ChainId::DEFAULT_ORBIT
+            );
+            ChainId::DEFAULT_ORBIT
         }
     };
 
@@ -203,6 +234,7 @@ fn load_rollup_metadata() -> Result<RollupMetadata, String> {
             debug!("ROLLUP_EXPLORER_URL not set, using default");
             "http://localhost:4000".to_string()
         }),
+        labels: parse_labels(&env::var("ROLLUP_LABELS").unwrap_or_default())?,
     };
 
     debug!("Rollup metadata loaded successfully");
@@ -1,7 +1,12 @@
 use avail_orbit_raas_blueprint_lib::OrbitContext;
-use avail_orbit_raas_blueprint_lib::config::OperatorConfig;
+use avail_orbit_raas_blueprint_lib::config::{
+    DeploymentConfig, OperatorConfig, default_compose_project_name,
+};
 use avail_orbit_raas_blueprint_lib::deployment::restart_containers;
-use avail_orbit_raas_blueprint_lib::types::{DeploymentStatus, RollupMetadata};
+use avail_orbit_raas_blueprint_lib::types::{
+    ChainId, DeploymentStatus, RollupMetadata, parse_labels,
+};
+use avail_orbit_raas_blueprint_lib::util::normalize_container_ids;
 use std::env;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -36,7 +41,7 @@ async fn main() -> Result<(), String> {
     let deployment_status = create_deployment_status().await?;
 
     // Create orbit context
-    let orbit_ctx = OrbitContext::new(operator_config);
+    let orbit_ctx = OrbitContext::new(operator_config)?;
 
     // Update the deployment status
     {
@@ -76,6 +81,7 @@ fn load_operator_config() -> Result<OperatorConfig, String> {
         fallback_s3_region: env::var("FALLBACKS3_REGION").ok(),
         fallback_s3_object_prefix: env::var("FALLBACKS3_OBJECT_PREFIX").ok(),
         fallback_s3_bucket: env::var("FALLBACKS3_BUCKET").ok(),
+        deployment: DeploymentConfig::from_env()?,
     };
 
     debug!("Operator configuration loaded successfully");
@@ -86,19 +92,24 @@ fn load_operator_config() -> Result<OperatorConfig, String> {
 async fn create_deployment_status() -> Result<DeploymentStatus, String> {
     debug!("Creating deployment status");
 
+    let chain_id = env::var("ROLLUP_CHAIN_ID")
+        .ok()
+        .and_then(|id| id.parse::<ChainId>().ok())
+        .unwrap_or_default();
+
     // Read container IDs from environment or detect
     let container_ids_str = env::var("ROLLUP_CONTAINER_IDS").unwrap_or_default();
     let container_ids = if container_ids_str.is_empty() {
         // Try to detect containers using docker ps
         debug!("No container IDs provided, attempting to detect...");
-        detect_containers().await?
+        let project_name = env::var("COMPOSE_PROJECT_NAME")
+            .unwrap_or_else(|_| default_compose_project_name(chain_id));
+        detect_containers(&project_name).await?
     } else {
         debug!("Using container IDs from environment");
-        container_ids_str
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .collect()
+        container_ids_str.split(',').map(String::from).collect()
     };
+    let container_ids = normalize_container_ids(container_ids);
 
     debug!(
         "Found {} container(s): {:?}",
@@ -112,10 +123,6 @@ async fn create_deployment_status() -> Result<DeploymentStatus, String> {
     }
 
     // Create deployment status
-    let chain_id = env::var("ROLLUP_CHAIN_ID")
-        .map(|id| id.parse::<u64>().unwrap_or(412346))
-        .unwrap_or(412346);
-
     let metadata = RollupMetadata {
         name: env::var("ROLLUP_NAME").unwrap_or_else(|_| "Avail Orbit Rollup".to_string()),
         chain_id,
@@ -128,6 +135,7 @@ async fn create_deployment_status() -> Result<DeploymentStatus, String> {
             .unwrap_or_else(|_| "http://localhost:8449".to_string()),
         explorer_url: env::var("ROLLUP_EXPLORER_URL")
             .unwrap_or_else(|_| "http://localhost:4000".to_string()),
+        labels: parse_labels(&env::var("ROLLUP_LABELS").unwrap_or_default())?,
     };
 
     let status = DeploymentStatus {
@@ -135,24 +143,23 @@ async fn create_deployment_status() -> Result<DeploymentStatus, String> {
         logs: vec!["Deployment status loaded from environment".to_string()],
         metadata: Some(metadata),
         container_ids,
+        ..Default::default()
     };
 
     info!("Deployment status created successfully");
     Ok(status)
 }
 
-/// Detect docker containers for the rollup
-async fn detect_containers() -> Result<Vec<String>, String> {
-    debug!("Attempting to detect containers with docker ps");
+/// Detect docker containers belonging to the rollup's compose project
+async fn detect_containers(project_name: &str) -> Result<Vec<String>, String> {
+    debug!(
+        "Attempting to detect containers for compose project {} with docker ps",
+        project_name
+    );
+    let project_filter = format!("label=com.docker.compose.project={}", project_name);
 
     let output = match tokio::process::Command::new("docker")
-        .args([
-            "ps",
-            "--format",
-            "{{.ID}}",
-            "--filter",
-            "name=orbit-setup-script",
-        ])
+        .args(["ps", "--format", "{{.ID}}", "--filter", &project_filter])
         .output()
         .await
     {
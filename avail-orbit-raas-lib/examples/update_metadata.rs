@@ -1,7 +1,9 @@
 use avail_orbit_raas_blueprint_lib::OrbitContext;
-use avail_orbit_raas_blueprint_lib::config::OperatorConfig;
+use avail_orbit_raas_blueprint_lib::config::{DeploymentConfig, OperatorConfig};
 use avail_orbit_raas_blueprint_lib::deployment::update_metadata;
-use avail_orbit_raas_blueprint_lib::types::{DeploymentStatus, RollupMetadata};
+use avail_orbit_raas_blueprint_lib::types::{
+    ChainId, DeploymentStatus, RollupMetadata, parse_labels,
+};
 use std::env;
 use tracing::{Level, debug, error, info};
 use tracing_subscriber::FmtSubscriber;
@@ -32,13 +34,14 @@ async fn main() -> Result<(), String> {
         logs: vec![],
         metadata: None,
         container_ids: vec![],
+        ..Default::default()
     };
 
     // Create a basic operator config
     let operator_config = load_operator_config()?;
 
     // Create orbit context
-    let orbit_ctx = OrbitContext::new(operator_config);
+    let orbit_ctx = OrbitContext::new(operator_config)?;
 
     // Set deployment status
     {
@@ -103,6 +106,7 @@ fn load_operator_config() -> Result<OperatorConfig, String> {
         fallback_s3_region: env::var("FALLBACKS3_REGION").ok(),
         fallback_s3_object_prefix: env::var("FALLBACKS3_OBJECT_PREFIX").ok(),
         fallback_s3_bucket: env::var("FALLBACKS3_BUCKET").ok(),
+        deployment: DeploymentConfig::from_env()?,
     };
 
     debug!("Operator configuration loaded successfully");
@@ -119,11 +123,11 @@ fn create_new_metadata() -> Result<RollupMetadata, String> {
         env::var("ROLLUP_CHAIN_ID").unwrap_or_else(|_| "412346".to_string())
     });
 
-    let chain_id = match chain_id_str.parse::<u64>() {
+    let chain_id = match chain_id_str.parse::<ChainId>() {
         Ok(id) => id,
         Err(e) => {
             error!("Invalid chain ID: {}", e);
-            return Err(format!("Invalid chain ID: {}", e));
+            return Err(e);
         }
     };
 
@@ -155,6 +159,11 @@ fn create_new_metadata() -> Result<RollupMetadata, String> {
         explorer_url: env::var("NEW_ROLLUP_EXPLORER_URL").unwrap_or_else(|_| {
             env::var("ROLLUP_EXPLORER_URL").unwrap_or_else(|_| "http://localhost:4000".to_string())
         }),
+        labels: parse_labels(
+            &env::var("NEW_ROLLUP_LABELS")
+                .or_else(|_| env::var("ROLLUP_LABELS"))
+                .unwrap_or_default(),
+        )?,
     };
 
     debug!("New rollup metadata created successfully");
@@ -0,0 +1,233 @@
+//! Cache of built orbit SDK dependencies
+//!
+//! Installing the orbit SDK's dependencies is the slowest part of a deployment, and its
+//! output only depends on the checked-out commit. The installed `node_modules` and build
+//! output are stored under the cache directory keyed by that commit, and restored instead
+//! of reinstalling when a later deployment checks out the same commit.
+
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Paths under the orbit SDK checkout that make up a cache entry, if they exist
+const CACHED_PATHS: &[&str] = &[
+    "node_modules",
+    "dist",
+    "examples/create-avail-rollup-eth/node_modules",
+];
+
+/// Suffix of entries moved aside while a new entry replaces them
+const ASIDE_SUFFIX: &str = ".old";
+
+/// Commit-keyed cache of a repository's installed dependencies
+#[derive(Clone, Debug)]
+pub struct ArtifactCache {
+    dir: PathBuf,
+}
+
+impl ArtifactCache {
+    /// Create a cache stored under `dir`
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Commit checked out in `repo_dir`, used as the cache key
+    pub async fn commit_key(repo_dir: &Path) -> Result<String, String> {
+        let output = Command::new("git")
+            .current_dir(repo_dir)
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run git rev-parse: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to read commit of {}: {}",
+                repo_dir.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Restore the entry for `key` into `repo_dir`
+    ///
+    /// Returns `false` without touching `repo_dir` if there is no entry for `key`.
+    pub async fn restore(&self, key: &str, repo_dir: &Path) -> Result<bool, String> {
+        let entry = self.dir.join(key);
+        if !entry.is_dir() {
+            return Ok(false);
+        }
+
+        for path in CACHED_PATHS {
+            let cached = entry.join(path);
+            if !cached.exists() {
+                continue;
+            }
+            let target = repo_dir.join(path);
+            if target.exists() {
+                tokio::fs::remove_dir_all(&target)
+                    .await
+                    .map_err(|e| format!("Failed to remove {}: {}", target.display(), e))?;
+            }
+            copy_dir(&cached, &target).await?;
+        }
+        Ok(true)
+    }
+
+    /// Store the installed dependencies in `repo_dir` as the entry for `key`
+    ///
+    /// Entries for other commits are removed, since a deployment only ever uses one.
+    pub async fn store(&self, key: &str, repo_dir: &Path) -> Result<(), String> {
+        let staging = self.dir.join(format!("{}.tmp", key));
+        if staging.exists() {
+            tokio::fs::remove_dir_all(&staging)
+                .await
+                .map_err(|e| format!("Failed to clear {}: {}", staging.display(), e))?;
+        }
+
+        for path in CACHED_PATHS {
+            let source = repo_dir.join(path);
+            if source.exists() {
+                copy_dir(&source, &staging.join(path)).await?;
+            }
+        }
+
+        // The previous entries are only deleted once the new one is in place, so a failed
+        // rename leaves the cache as it was
+        let previous = self.set_aside_entries().await?;
+        if let Err(e) = tokio::fs::rename(&staging, self.dir.join(key)).await {
+            restore_entries(&previous).await;
+            return Err(format!("Failed to store cache entry {}: {}", key, e));
+        }
+        for (_, aside) in previous {
+            if let Err(e) = tokio::fs::remove_dir_all(&aside).await {
+                tracing::warn!(
+                    "Failed to remove old cache entry {}: {}",
+                    aside.display(),
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Rename every complete entry aside, returning each entry's path and where it went
+    ///
+    /// Entries left aside by an interrupted store are removed first. If an entry can't be
+    /// moved, the ones already moved are put back.
+    async fn set_aside_entries(&self) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Ok(Vec::new());
+        };
+
+        let mut moved = Vec::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.ends_with(".tmp") {
+                continue;
+            }
+            if name.ends_with(ASIDE_SUFFIX) {
+                tokio::fs::remove_dir_all(entry.path())
+                    .await
+                    .map_err(|e| format!("Failed to remove cache entry {}: {}", name, e))?;
+                continue;
+            }
+
+            let aside = self.dir.join(format!("{}{}", name, ASIDE_SUFFIX));
+            if let Err(e) = tokio::fs::rename(entry.path(), &aside).await {
+                restore_entries(&moved).await;
+                return Err(format!("Failed to move cache entry {}: {}", name, e));
+            }
+            moved.push((entry.path(), aside));
+        }
+        Ok(moved)
+    }
+}
+
+/// Move entries set aside by [`ArtifactCache::set_aside_entries`] back into place
+async fn restore_entries(moved: &[(PathBuf, PathBuf)]) {
+    for (entry, aside) in moved {
+        if let Err(e) = tokio::fs::rename(aside, entry).await {
+            tracing::warn!("Failed to restore cache entry {}: {}", entry.display(), e);
+        }
+    }
+}
+
+/// Copy the directory `source` to `target`, preserving symlinks and permissions
+async fn copy_dir(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let output = Command::new("cp")
+        .arg("-a")
+        .arg(source)
+        .arg(target)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run cp: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to copy {}: {}",
+            source.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn store_replaces_previous_entry() {
+        let base =
+            std::env::temp_dir().join(format!("avail-orbit-raas-cache-{}", std::process::id()));
+        let repo = base.join("repo");
+        let cache = ArtifactCache::new(base.join("cache"));
+        std::fs::create_dir_all(repo.join("node_modules")).unwrap();
+        std::fs::create_dir_all(base.join("cache")).unwrap();
+
+        std::fs::write(repo.join("node_modules/a"), "first").unwrap();
+        cache.store("aaa", &repo).await.unwrap();
+        std::fs::write(repo.join("node_modules/a"), "second").unwrap();
+        let stored = cache.store("bbb", &repo).await;
+
+        let mut names: Vec<String> = std::fs::read_dir(base.join("cache"))
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        let content = std::fs::read_to_string(base.join("cache/bbb/node_modules/a"));
+        let _ = std::fs::remove_dir_all(&base);
+
+        stored.unwrap();
+        assert_eq!(names, ["bbb"]);
+        assert_eq!(content.unwrap(), "second");
+    }
+
+    #[tokio::test]
+    async fn failed_store_keeps_previous_entry() {
+        let base = std::env::temp_dir().join(format!(
+            "avail-orbit-raas-cache-failed-{}",
+            std::process::id()
+        ));
+        let repo = base.join("repo");
+        let cache = ArtifactCache::new(base.join("cache"));
+        std::fs::create_dir_all(repo.join("node_modules")).unwrap();
+        std::fs::create_dir_all(base.join("cache")).unwrap();
+        cache.store("aaa", &repo).await.unwrap();
+
+        // The staging directory of a nested key sits inside an entry that is set aside
+        // with the others, so the final rename has no parent to move into
+        let stored = cache.store("missing/bbb", &repo).await;
+        let kept = base.join("cache/aaa").is_dir();
+        let _ = std::fs::remove_dir_all(&base);
+
+        assert!(stored.is_err());
+        assert!(kept);
+    }
+}
@@ -0,0 +1,134 @@
+//! Avail network checks for Avail Orbit RaaS
+//!
+//! Queries the configured Avail node to confirm the rollup's app ID exists on that network,
+//! catching an app ID registered on a different Avail network than the one configured.
+
+use crate::rpc::json_rpc_request;
+use crate::types::AvailAppId;
+use serde_json::json;
+use std::time::Duration;
+
+/// Storage key of `DataAvailability::NextAppId`, i.e.
+/// `twox128("DataAvailability") ++ twox128("NextAppId")`
+const NEXT_APP_ID_STORAGE_KEY: &str =
+    "0x905e59f6c8fc974ec64116e6f6479928bd215f8ad159d04103d61a3d430fee34";
+
+/// Upper bound on checking the app ID, so an unreachable Avail node can't stall a deployment
+const VERIFY_APP_ID_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Query the chain name reported by an Avail node, e.g. `Avail Turing Testnet`
+pub async fn get_network_name(client: &reqwest::Client, api_url: &str) -> Result<String, String> {
+    let result = json_rpc_request(client, api_url, "system_chain", json!([])).await?;
+    result
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("Expected a chain name, got {}", result))
+}
+
+/// Query the next app ID to be registered on an Avail network
+pub async fn get_next_app_id(client: &reqwest::Client, api_url: &str) -> Result<u32, String> {
+    let result = json_rpc_request(
+        client,
+        api_url,
+        "state_getStorage",
+        json!([NEXT_APP_ID_STORAGE_KEY]),
+    )
+    .await?;
+
+    // An unset value means no app IDs have been registered beyond the defaults
+    let Some(hex) = result.as_str() else {
+        return Ok(0);
+    };
+    let bytes = decode_hex(hex)?;
+    decode_compact_u32(&bytes)
+}
+
+/// Check that `app_id` is registered on the Avail network served at `api_url`
+///
+/// Returns the network name on success. Fails if the node doesn't answer within
+/// [`VERIFY_APP_ID_TIMEOUT`].
+pub async fn verify_app_id(
+    client: &reqwest::Client,
+    api_url: &str,
+    app_id: AvailAppId,
+) -> Result<String, String> {
+    let (network, next_app_id) = tokio::time::timeout(VERIFY_APP_ID_TIMEOUT, async {
+        Ok::<_, String>((
+            get_network_name(client, api_url).await?,
+            get_next_app_id(client, api_url).await?,
+        ))
+    })
+    .await
+    .map_err(|_| {
+        format!(
+            "Avail node did not respond within {}s",
+            VERIFY_APP_ID_TIMEOUT.as_secs()
+        )
+    })??;
+
+    if app_id.get() >= next_app_id {
+        return Err(format!(
+            "Avail app ID {} is not registered on {} (next app ID is {}); it may belong to a \
+             different Avail network",
+            app_id, network, next_app_id
+        ));
+    }
+
+    Ok(network)
+}
+
+/// Check that a development seed such as `//Alice` is only used on a development network
+pub fn check_seed_network(seed: &str, network: &str) -> Result<(), String> {
+    let is_dev_seed = seed.trim_start().starts_with("//");
+    let is_dev_network = network.to_lowercase().contains("dev");
+
+    if is_dev_seed && !is_dev_network {
+        return Err(format!(
+            "Avail seed is a development account, which can't submit data on {}",
+            network
+        ));
+    }
+    Ok(())
+}
+
+/// Decode a `0x`-prefixed hex string into bytes
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let digits = hex.trim_start_matches("0x");
+    if digits.len() % 2 != 0 {
+        return Err(format!("Odd-length hex string '{}'", hex));
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|e| format!("Invalid hex string '{}': {}", hex, e))
+        })
+        .collect()
+}
+
+/// Decode a SCALE compact-encoded `u32`
+fn decode_compact_u32(bytes: &[u8]) -> Result<u32, String> {
+    let first = *bytes.first().ok_or("Empty compact integer")?;
+    let read_le = |len: usize| -> Result<u32, String> {
+        let slice = bytes
+            .get(..len)
+            .ok_or_else(|| format!("Truncated compact integer: {:?}", bytes))?;
+        Ok(slice
+            .iter()
+            .rev()
+            .fold(0u32, |acc, byte| (acc << 8) | u32::from(*byte)))
+    };
+
+    match first & 0b11 {
+        0b00 => Ok(u32::from(first >> 2)),
+        0b01 => Ok(read_le(2)? >> 2),
+        0b10 => Ok(read_le(4)? >> 2),
+        _ => {
+            let value = bytes
+                .get(1..5)
+                .ok_or_else(|| format!("Truncated compact integer: {:?}", bytes))?;
+            Ok(u32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+        }
+    }
+}
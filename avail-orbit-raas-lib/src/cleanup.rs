@@ -0,0 +1,206 @@
+//! Cleanup of orphaned deployment directories
+//!
+//! Failed or abandoned deployments under a previous working directory keep their cloned
+//! repositories and chain data around. This finds deployment directories next to the
+//! current working directory that nothing references anymore, and removes them on request.
+//! Only directories this service marked as its own are ever considered, since the parent
+//! directory may be shared with other services.
+
+use crate::OrbitContext;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::process::Command;
+
+/// File this service writes into every deployment working directory it sets up
+const WORKING_DIR_MARKER: &str = ".avail-orbit-raas";
+
+/// Mark `working_dir` as a deployment directory of this service that is in use now
+///
+/// Called whenever a deployment starts using the directory, so the marker's modification
+/// time tells cleanup whether a deployment may still be running there.
+pub fn mark_working_dir(working_dir: &Path) -> Result<(), String> {
+    let marker = working_dir.join(WORKING_DIR_MARKER);
+    std::fs::write(&marker, b"").map_err(|e| format!("Failed to write {}: {}", marker.display(), e))
+}
+
+/// A deployment directory no longer referenced by the service
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrphanedDeployment {
+    /// Directory path
+    pub path: PathBuf,
+    /// Total size of the directory's files, in bytes
+    pub size_bytes: u64,
+}
+
+/// Result of an orphan cleanup
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OrphanCleanup {
+    /// Whether this was a dry run that removed nothing
+    pub dry_run: bool,
+    /// Orphaned directories found
+    pub orphans: Vec<OrphanedDeployment>,
+    /// Bytes freed, or that would be freed on a dry run
+    pub reclaimed_bytes: u64,
+    /// Directories that could not be removed, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Find deployment directories next to the current working directory that are orphaned
+///
+/// A directory is orphaned if this service marked it as a deployment directory, it isn't
+/// the current working directory, no container was started from it, and no deployment
+/// used it within the deployment timeout, as one may still be setting it up.
+pub async fn list_orphaned_deployments(
+    context: &OrbitContext,
+) -> Result<Vec<OrphanedDeployment>, String> {
+    let working_dir = context.working_dir().await;
+    let in_use_window = context.deployment_config().await.deployment_timeout();
+    let base_dir = working_dir
+        .parent()
+        .ok_or_else(|| "Working directory has no parent to scan".to_string())?;
+
+    let entries = std::fs::read_dir(base_dir)
+        .map_err(|e| format!("Failed to read {}: {}", base_dir.display(), e))?;
+
+    let mut orphans = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path == working_dir
+            || !is_deployment_dir(&path)
+            || marked_within(&path, in_use_window)
+            || has_containers(&path).await
+        {
+            continue;
+        }
+
+        let scan_path = path.clone();
+        let size_bytes = tokio::task::spawn_blocking(move || dir_size(&scan_path))
+            .await
+            .unwrap_or_default();
+        orphans.push(OrphanedDeployment { path, size_bytes });
+    }
+
+    orphans.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(orphans)
+}
+
+/// Remove orphaned deployment directories, or only report them if `dry_run` is set
+pub async fn cleanup_orphaned_deployments(
+    context: &OrbitContext,
+    dry_run: bool,
+) -> Result<OrphanCleanup, String> {
+    let orphans = list_orphaned_deployments(context).await?;
+    let mut cleanup = OrphanCleanup {
+        dry_run,
+        ..Default::default()
+    };
+
+    for orphan in &orphans {
+        if dry_run {
+            cleanup.reclaimed_bytes += orphan.size_bytes;
+            continue;
+        }
+
+        match tokio::fs::remove_dir_all(&orphan.path).await {
+            Ok(()) => {
+                cleanup.reclaimed_bytes += orphan.size_bytes;
+                context
+                    .log(&format!(
+                        "Removed orphaned deployment {} ({} bytes)",
+                        orphan.path.display(),
+                        orphan.size_bytes
+                    ))
+                    .await;
+            }
+            Err(e) => cleanup
+                .errors
+                .push(format!("{}: {}", orphan.path.display(), e)),
+        }
+    }
+
+    cleanup.orphans = orphans;
+    Ok(cleanup)
+}
+
+/// Whether `path` is a deployment directory this service marked as its own
+fn is_deployment_dir(path: &Path) -> bool {
+    path.is_dir() && path.join(WORKING_DIR_MARKER).is_file()
+}
+
+/// Whether a deployment marked `path` as in use less than `window` ago
+///
+/// A marker with an unreadable time counts as recent, so nothing is removed by mistake.
+fn marked_within(path: &Path, window: Duration) -> bool {
+    std::fs::metadata(path.join(WORKING_DIR_MARKER))
+        .and_then(|metadata| metadata.modified())
+        .map_or(true, |modified| {
+            SystemTime::now()
+                .duration_since(modified)
+                .map_or(true, |age| age < window)
+        })
+}
+
+/// Whether any container, running or stopped, was started by compose from this deployment
+///
+/// If Docker can't be queried the directory is treated as in use, so nothing is removed.
+async fn has_containers(deployment_dir: &Path) -> bool {
+    let setup_dir = deployment_dir.join("orbit-setup-script");
+    let filter = format!(
+        "label=com.docker.compose.project.working_dir={}",
+        setup_dir.display()
+    );
+
+    match Command::new("docker")
+        .args(["ps", "-aq", "--filter", &filter])
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => !output.stdout.trim_ascii().is_empty(),
+        _ => true,
+    }
+}
+
+/// Total size of the files under `path`, not following symlinks
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => {
+                entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_marked_directories_are_deployments() {
+        let base =
+            std::env::temp_dir().join(format!("avail-orbit-raas-cleanup-{}", std::process::id()));
+        let ours = base.join("ours");
+        let theirs = base.join("theirs");
+        std::fs::create_dir_all(ours.join("orbit-setup-script")).unwrap();
+        std::fs::create_dir_all(theirs.join("orbit-setup-script")).unwrap();
+        mark_working_dir(&ours).unwrap();
+
+        let (ours_found, theirs_found) = (is_deployment_dir(&ours), is_deployment_dir(&theirs));
+        let recent = marked_within(&ours, Duration::from_secs(3600));
+        let stale = marked_within(&ours, Duration::ZERO);
+        let _ = std::fs::remove_dir_all(&base);
+
+        assert!(ours_found);
+        assert!(!theirs_found);
+        assert!(recent);
+        assert!(!stale);
+    }
+}
@@ -4,8 +4,17 @@
 //! OperatorConfig contains sensitive information like private keys and is never exposed in job arguments.
 //! The AvailOrbitConfig is derived from operator config + rollup metadata for deployment.
 
-use crate::types::RollupMetadata;
+use crate::types::{AvailAppId, ChainId, RollupMetadata};
+use crate::util::{
+    ANVIL_DEFAULT_KEYS, create_private_dir, is_secret_key, redact_url, validate_private_key,
+    validate_s3_prefix, validate_url,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Operator configuration containing private keys
 ///
@@ -27,6 +36,1441 @@ pub struct OperatorConfig {
     pub fallback_s3_region: Option<String>,
     pub fallback_s3_object_prefix: Option<String>,
     pub fallback_s3_bucket: Option<String>,
+    /// Non-sensitive deployment settings chosen by the operator
+    #[serde(default)]
+    pub deployment: DeploymentConfig,
+}
+
+/// Prefix of environment variables passed through to the deploy scripts, e.g.
+/// `SCRIPT_ENV_FOO=bar` sets `FOO=bar`
+pub const SCRIPT_ENV_PREFIX: &str = "SCRIPT_ENV_";
+
+/// Variables the service sets for the deploy scripts itself, which extra variables can't override
+const RESERVED_SCRIPT_ENV: &[&str] = &[
+    "PRIVATE_KEY",
+    "DEPLOYER_PRIVATE_KEY",
+    "BATCH_POSTER_PRIVATE_KEY",
+    "VALIDATOR_PRIVATE_KEY",
+    "AVAIL_ADDR_SEED",
+    "AVAIL_APP_ID",
+    "PARENT_CHAIN_RPC",
+    "L2_RPC_URL",
+    "L3_RPC_URL",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "NO_PROXY",
+    "NPM_CONFIG_PROXY",
+    "NPM_CONFIG_HTTPS_PROXY",
+];
+
+/// Placeholder used in place of secrets when a config is displayed or exported
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Upper bound on any single HTTP request, so an unresponsive RPC can't stall a job
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Upper bound on establishing an HTTP connection
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl OperatorConfig {
+    /// Check that every private key is set to a real key, not a placeholder or test key
+    ///
+    /// Test keys are allowed when deploying to a local fork, where they hold the funds.
+    /// Every invalid key is reported, not just the first.
+    pub fn validate_keys(&self) -> Result<(), String> {
+        let local = self.deployment.settlement_layer == SettlementLayer::LocalFork;
+        collect_errors([
+            validate_private_key("DEPLOYER_PRIVATE_KEY", &self.deployer_private_key, local),
+            validate_private_key(
+                "BATCH_POSTER_PRIVATE_KEY",
+                &self.batch_poster_private_key,
+                local,
+            ),
+            validate_private_key("VALIDATOR_PRIVATE_KEY", &self.validator_private_key, local),
+        ])
+    }
+
+    /// On a local fork, use funded anvil accounts for the keys that aren't set
+    ///
+    /// The deployer, batch poster and validator get anvil's default accounts 0, 1 and 2.
+    pub fn fill_local_fork_keys(&mut self) {
+        if self.deployment.settlement_layer != SettlementLayer::LocalFork {
+            return;
+        }
+        let keys = [
+            &mut self.deployer_private_key,
+            &mut self.batch_poster_private_key,
+            &mut self.validator_private_key,
+        ];
+        for (key, anvil_key) in keys.into_iter().zip(ANVIL_DEFAULT_KEYS) {
+            if key.trim().is_empty() {
+                *key = format!("0x{}", anvil_key);
+            }
+        }
+    }
+
+    /// S3 fallback object prefix for the rollup with the given chain ID
+    pub fn fallback_s3_object_prefix(&self, chain_id: ChainId) -> String {
+        self.fallback_s3_object_prefix
+            .clone()
+            .filter(|prefix| !prefix.is_empty())
+            .unwrap_or_else(|| default_s3_object_prefix(chain_id))
+    }
+
+    /// Get a copy of this config with all secrets replaced by [`REDACTED`]
+    ///
+    /// Optional secrets are only redacted if set, so the copy still shows which are configured.
+    /// URLs, which may carry API keys or proxy credentials, are reduced by [`redact_url`].
+    pub fn redacted(&self) -> Self {
+        let redact = |value: &Option<String>| value.as_ref().map(|_| REDACTED.to_string());
+        let redact_optional_url = |url: &Option<String>| url.as_deref().map(redact_url);
+        let deployment = &self.deployment;
+
+        Self {
+            deployer_private_key: REDACTED.to_string(),
+            batch_poster_private_key: REDACTED.to_string(),
+            validator_private_key: REDACTED.to_string(),
+            avail_addr_seed: REDACTED.to_string(),
+            fallback_s3_access_key: redact(&self.fallback_s3_access_key),
+            fallback_s3_secret_key: redact(&self.fallback_s3_secret_key),
+            fallback_s3_region: self.fallback_s3_region.clone(),
+            fallback_s3_object_prefix: self.fallback_s3_object_prefix.clone(),
+            fallback_s3_bucket: self.fallback_s3_bucket.clone(),
+            deployment: DeploymentConfig {
+                extra_env: self
+                    .deployment
+                    .extra_env
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = if is_secret_key(name) {
+                            REDACTED.to_string()
+                        } else {
+                            value.clone()
+                        };
+                        (name.clone(), value)
+                    })
+                    .collect(),
+                anvil_fork_url: redact_optional_url(&deployment.anvil_fork_url),
+                s3_endpoint_url: redact_optional_url(&deployment.s3_endpoint_url),
+                tangle_rpc_url: redact_url(&deployment.tangle_rpc_url),
+                parent_chain_fallback_rpcs: deployment
+                    .parent_chain_fallback_rpcs
+                    .iter()
+                    .map(|rpc| redact_url(rpc))
+                    .collect(),
+                avail_api_url: redact_url(&deployment.avail_api_url),
+                proxy: ProxyConfig {
+                    http_proxy: redact_optional_url(&deployment.proxy.http_proxy),
+                    https_proxy: redact_optional_url(&deployment.proxy.https_proxy),
+                    no_proxy: deployment.proxy.no_proxy.clone(),
+                },
+                ..deployment.clone()
+            },
+        }
+    }
+}
+
+/// Operator-controlled deployment settings
+///
+/// Unlike the rest of [`OperatorConfig`] these contain no secrets, but they are still
+/// chosen by the operator rather than passed through job arguments.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeploymentConfig {
+    /// Layer the rollup settles to
+    #[serde(default)]
+    pub settlement_layer: SettlementLayer,
+    /// Launch an anvil container serving the parent chain RPC, in local fork mode
+    #[serde(default)]
+    pub launch_anvil: bool,
+    /// Chain the launched anvil forks; a fresh chain is started if unset
+    #[serde(default)]
+    pub anvil_fork_url: Option<String>,
+    /// Endpoint of an S3-compatible store used for log exports instead of AWS S3
+    #[serde(default)]
+    pub s3_endpoint_url: Option<String>,
+    /// Tangle node HTTP RPC endpoint
+    ///
+    /// The service replaces this with the blueprint runner's endpoint when it starts.
+    #[serde(default = "default_tangle_rpc_url")]
+    pub tangle_rpc_url: String,
+    /// Directory the repositories are cloned into and the `.env` file is written to
+    #[serde(default = "default_working_dir")]
+    pub working_dir: PathBuf,
+    /// Number of attempts made when waiting for an RPC endpoint to become ready
+    #[serde(default = "default_rpc_probe_attempts")]
+    pub rpc_probe_attempts: u32,
+    /// Seconds between RPC readiness attempts
+    #[serde(default = "default_rpc_probe_interval_secs")]
+    pub rpc_probe_interval_secs: u64,
+    /// Outbound proxy settings for spawned commands and HTTP clients
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    /// Deploy fresh rollup contracts even if a previous deployment is still live
+    #[serde(default)]
+    pub force_redeploy: bool,
+    /// Upper bound in seconds on the deployment steps, after which the deployment is aborted
+    ///
+    /// The health wait afterwards is bounded by `health_wait_secs` instead.
+    #[serde(default = "default_deployment_timeout_secs")]
+    pub deployment_timeout_secs: u64,
+    /// Seconds to wait in total for the rollup's containers to become healthy and its RPC to
+    /// respond before reporting it unhealthy
+    #[serde(default = "default_health_wait_secs")]
+    pub health_wait_secs: u64,
+    /// Seconds `docker compose down` waits for containers to stop before killing them
+    #[serde(default = "default_compose_down_timeout_secs")]
+    pub compose_down_timeout_secs: u64,
+    /// Seconds the node image pull may take before it is aborted
+    #[serde(default = "default_image_pull_timeout_secs")]
+    pub image_pull_timeout_secs: u64,
+    /// Seconds each long-running deployment step may take
+    #[serde(default)]
+    pub step_timeouts: StepTimeouts,
+    /// Docker compose project name; defaults to one derived from the chain ID
+    #[serde(default)]
+    pub compose_project_name: Option<String>,
+    /// Compose file merged on top of the setup script's, e.g. to add sidecars or volumes
+    #[serde(default)]
+    pub compose_override_file: Option<PathBuf>,
+    /// Seconds a prewarm stays fresh enough for a deployment to skip the pull and clone
+    #[serde(default = "default_prewarm_max_age_secs")]
+    pub prewarm_max_age_secs: u64,
+    /// History depth of repository clones; 0 clones the full history
+    #[serde(default = "default_git_clone_depth")]
+    pub git_clone_depth: u32,
+    /// Only check out the parts of the orbit SDK the deployment uses
+    #[serde(default)]
+    pub git_sparse_checkout: bool,
+    /// Directory caching the orbit SDK's installed dependencies by commit; unset disables it
+    #[serde(default)]
+    pub artifact_cache_dir: Option<PathBuf>,
+    /// Seconds batch posting may trail the rollup's latest block before it is flagged
+    #[serde(default = "default_max_batch_lag_secs")]
+    pub max_batch_lag_secs: u64,
+    /// Compose service name patterns in the order their containers are stopped; they
+    /// are started in reverse
+    #[serde(default = "default_stop_order")]
+    pub stop_order: Vec<String>,
+    /// Extra environment variables for the contract deploy and bridge setup scripts
+    #[serde(default)]
+    pub extra_env: BTreeMap<String, String>,
+    /// Parent chain RPCs the service fails over to when the primary one is unreachable
+    #[serde(default)]
+    pub parent_chain_fallback_rpcs: Vec<String>,
+    /// Re-run a deployment found interrupted on startup, instead of leaving it for cleanup
+    #[serde(default = "default_resume_interrupted")]
+    pub resume_interrupted: bool,
+    /// Host ports published by the rollup's compose stack
+    #[serde(default)]
+    pub ports: PortAllocation,
+    /// Run a read-only node next to the sequencer that serves RPC and forwards transactions
+    #[serde(default)]
+    pub read_replica: bool,
+    /// Host address the node's Prometheus metrics are published on
+    #[serde(default = "default_diagnostics_bind_addr")]
+    pub metrics_bind_addr: IpAddr,
+    /// Host address the node's pprof profiler is published on
+    #[serde(default = "default_diagnostics_bind_addr")]
+    pub pprof_bind_addr: IpAddr,
+    /// Whether this is a development or production deployment
+    #[serde(default)]
+    pub profile: DeploymentProfile,
+    /// Minimum CPUs required to deploy
+    #[serde(default = "default_min_cpus")]
+    pub min_cpus: usize,
+    /// Minimum available memory in GB required to deploy
+    #[serde(default = "default_min_memory_gb")]
+    pub min_memory_gb: u64,
+    /// Minimum free disk in GB in the working directory required to deploy
+    #[serde(default = "default_min_disk_gb")]
+    pub min_disk_gb: u64,
+    /// JSON-RPC endpoint of the Avail network the rollup posts data to
+    #[serde(default = "default_avail_api_url")]
+    pub avail_api_url: String,
+    /// File the deployment status is persisted to; defaults to one in the working directory
+    #[serde(default)]
+    pub status_path: Option<PathBuf>,
+    /// Seconds between periodic status flushes
+    #[serde(default = "default_status_flush_interval_secs")]
+    pub status_flush_interval_secs: u64,
+    /// Where the contract deploy and bridge setup scripts run
+    #[serde(default)]
+    pub execution_mode: ExecutionMode,
+    /// Nitro node image the rollup runs, e.g. to pin a patched build
+    #[serde(default = "default_node_image")]
+    pub node_image: String,
+    /// Node.js image used for the scripts when running in [`ExecutionMode::Container`]
+    #[serde(default = "default_toolchain_image")]
+    pub toolchain_image: String,
+    /// Package manager that installs dependencies and runs the deploy scripts
+    #[serde(default)]
+    pub package_manager: PackageManager,
+    /// Bytes of stdout and of stderr kept from each deploy script; the middle of longer
+    /// output is dropped
+    #[serde(default = "default_max_captured_output_bytes")]
+    pub max_captured_output_bytes: usize,
+}
+
+/// JavaScript package manager used to install dependencies and run the deploy scripts
+///
+/// Each supports `install` and `run <script>`, so only the program differs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Npm,
+    #[default]
+    Yarn,
+    Pnpm,
+    Bun,
+}
+
+impl PackageManager {
+    /// Name of the package manager's executable
+    pub fn program(&self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Bun => "bun",
+        }
+    }
+
+    /// Arguments that run `script` from `package.json`, passing `args` on to the script
+    ///
+    /// npm parses options after the script name itself unless they follow a `--`.
+    pub fn run_args<'a>(&self, script: &'a str, args: &[&'a str]) -> Vec<&'a str> {
+        let mut run = vec!["run", script];
+        if *self == PackageManager::Npm {
+            run.push("--");
+        }
+        run.extend_from_slice(args);
+        run
+    }
+
+    /// Whether the official Node.js images used as the toolchain image ship this manager
+    pub fn in_node_image(&self) -> bool {
+        matches!(self, PackageManager::Npm | PackageManager::Yarn)
+    }
+}
+
+impl std::str::FromStr for PackageManager {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "npm" => Ok(PackageManager::Npm),
+            "yarn" => Ok(PackageManager::Yarn),
+            "pnpm" => Ok(PackageManager::Pnpm),
+            "bun" => Ok(PackageManager::Bun),
+            other => Err(format!("Unknown package manager: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program())
+    }
+}
+
+/// Where the deployment's package manager scripts are executed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    /// Use the host's node and package manager
+    #[default]
+    Host,
+    /// Run inside the pinned toolchain image, so the host only needs Docker
+    Container,
+}
+
+impl std::str::FromStr for ExecutionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "host" => Ok(ExecutionMode::Host),
+            "container" | "docker" => Ok(ExecutionMode::Container),
+            other => Err(format!("Unknown execution mode: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionMode::Host => write!(f, "host"),
+            ExecutionMode::Container => write!(f, "container"),
+        }
+    }
+}
+
+/// Deployment profile, which controls how strictly risky settings are rejected
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeploymentProfile {
+    /// Risky settings only produce warnings
+    #[default]
+    Development,
+    /// Risky settings are rejected
+    Production,
+}
+
+impl std::str::FromStr for DeploymentProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "development" | "dev" => Ok(DeploymentProfile::Development),
+            "production" | "prod" => Ok(DeploymentProfile::Production),
+            other => Err(format!("Unknown deployment profile: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for DeploymentProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeploymentProfile::Development => write!(f, "development"),
+            DeploymentProfile::Production => write!(f, "production"),
+        }
+    }
+}
+
+/// Seconds each long-running deployment step may take before it is abandoned
+///
+/// A step that times out fails the deployment, and the commands it started are killed.
+/// The node image pull has its own `image_pull_timeout_secs`, applied to each attempt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StepTimeouts {
+    /// Cloning or updating the repositories, including retries
+    pub clone_secs: u64,
+    /// Installing the deploy scripts' dependencies
+    pub install_secs: u64,
+    /// Running the contract deploy script
+    pub deploy_contracts_secs: u64,
+    /// Starting the chain with docker compose
+    pub start_chain_secs: u64,
+    /// Running the token bridge setup script
+    pub token_bridge_secs: u64,
+}
+
+impl Default for StepTimeouts {
+    fn default() -> Self {
+        Self {
+            clone_secs: 600,
+            install_secs: 600,
+            deploy_contracts_secs: 900,
+            start_chain_secs: 300,
+            token_bridge_secs: 900,
+        }
+    }
+}
+
+impl StepTimeouts {
+    /// Apply the `*_TIMEOUT_SECS` overrides set in the environment
+    fn load_env(&mut self) -> Result<(), String> {
+        let timeouts = [
+            ("CLONE_TIMEOUT_SECS", &mut self.clone_secs),
+            ("INSTALL_TIMEOUT_SECS", &mut self.install_secs),
+            (
+                "DEPLOY_CONTRACTS_TIMEOUT_SECS",
+                &mut self.deploy_contracts_secs,
+            ),
+            ("START_CHAIN_TIMEOUT_SECS", &mut self.start_chain_secs),
+            ("TOKEN_BRIDGE_TIMEOUT_SECS", &mut self.token_bridge_secs),
+        ];
+        for (name, secs) in timeouts {
+            if let Ok(value) = env::var(name) {
+                *secs = value
+                    .parse()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .ok_or_else(|| format!("Invalid {}: expected a positive integer", name))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Host ports of every service the rollup's compose stack publishes
+///
+/// Container-side ports never change; only the host side of each mapping is moved, so
+/// several rollups can run on one host by giving each a different offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortAllocation {
+    /// Node HTTP RPC
+    pub rpc: u16,
+    /// Node WebSocket RPC
+    pub ws: u16,
+    /// Sequencer feed
+    pub feed: u16,
+    /// Block explorer
+    pub explorer: u16,
+    /// Node Prometheus metrics
+    pub metrics: u16,
+    /// Node pprof profiler
+    pub pprof: u16,
+    /// Read replica HTTP RPC
+    #[serde(default = "default_replica_rpc_port")]
+    pub replica_rpc: u16,
+}
+
+impl Default for PortAllocation {
+    fn default() -> Self {
+        Self {
+            rpc: 8449,
+            ws: 8548,
+            feed: 9642,
+            explorer: 4000,
+            metrics: 6070,
+            pprof: 6071,
+            replica_rpc: default_replica_rpc_port(),
+        }
+    }
+}
+
+fn default_replica_rpc_port() -> u16 {
+    8450
+}
+
+impl PortAllocation {
+    /// Shift every port by `offset`, failing on overflow or if any two ports collide
+    pub fn with_offset(&self, offset: u16) -> Result<Self, String> {
+        let shift = |name: &str, port: u16| {
+            port.checked_add(offset).ok_or_else(|| {
+                format!(
+                    "Port offset {} moves the {} port {} past 65535",
+                    offset, name, port
+                )
+            })
+        };
+
+        let ports = Self {
+            rpc: shift("rpc", self.rpc)?,
+            ws: shift("ws", self.ws)?,
+            feed: shift("feed", self.feed)?,
+            explorer: shift("explorer", self.explorer)?,
+            metrics: shift("metrics", self.metrics)?,
+            pprof: shift("pprof", self.pprof)?,
+            replica_rpc: shift("replica_rpc", self.replica_rpc)?,
+        };
+        ports.validate()?;
+        Ok(ports)
+    }
+
+    /// All ports with their service names
+    pub fn all(&self) -> [(&'static str, u16); 7] {
+        [
+            ("rpc", self.rpc),
+            ("ws", self.ws),
+            ("feed", self.feed),
+            ("explorer", self.explorer),
+            ("metrics", self.metrics),
+            ("pprof", self.pprof),
+            ("replica_rpc", self.replica_rpc),
+        ]
+    }
+
+    /// Check that no two services share a port
+    pub fn validate(&self) -> Result<(), String> {
+        let ports = self.all();
+        for (i, (name, port)) in ports.iter().enumerate() {
+            if let Some((other, _)) = ports[i + 1..].iter().find(|(_, other)| other == port) {
+                return Err(format!(
+                    "Ports collide: {} and {} both use {}",
+                    name, other, port
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// URL of the node's HTTP RPC on the host
+    pub fn rpc_url(&self) -> String {
+        format!("http://localhost:{}", self.rpc)
+    }
+
+    /// URL of the read replica's HTTP RPC on the host
+    pub fn replica_rpc_url(&self) -> String {
+        format!("http://localhost:{}", self.replica_rpc)
+    }
+
+    /// URL of the node's Prometheus metrics on the host
+    pub fn metrics_url(&self) -> String {
+        format!("http://localhost:{}/debug/metrics/prometheus", self.metrics)
+    }
+}
+
+/// Variables configuration is loaded from
+///
+/// The process environment, overlaid with values read from a file such as `.env`. Reading
+/// through an overlay lets the configuration be reloaded without modifying the environment
+/// of the running process.
+#[derive(Clone, Debug, Default)]
+pub struct EnvVars {
+    overlay: BTreeMap<String, String>,
+}
+
+impl EnvVars {
+    /// Read the process environment, with values in `overlay` taking precedence
+    pub fn with_overlay(overlay: BTreeMap<String, String>) -> Self {
+        Self { overlay }
+    }
+
+    /// Get the value of a variable
+    pub fn var(&self, name: &str) -> Result<String, env::VarError> {
+        match self.overlay.get(name) {
+            Some(value) => Ok(value.clone()),
+            None => env::var(name),
+        }
+    }
+
+    /// Get every variable, sorted by name
+    pub fn vars(&self) -> BTreeMap<String, String> {
+        let mut vars: BTreeMap<String, String> = env::vars().collect();
+        vars.extend(self.overlay.clone());
+        vars
+    }
+}
+
+/// Hosts that always bypass the proxy, since the rollup and its probes are on this machine
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+/// Outbound HTTP proxy settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// Proxy for plain HTTP traffic
+    pub http_proxy: Option<String>,
+    /// Proxy for HTTPS traffic
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts that bypass the proxy
+    pub no_proxy: Option<String>,
+}
+
+impl ProxyConfig {
+    /// Load proxy settings from the conventional environment variables
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_vars(&EnvVars::default())
+    }
+
+    /// Load proxy settings from the conventional variables in `vars`
+    pub fn from_vars(vars: &EnvVars) -> Result<Self, String> {
+        let var = |name: &str| {
+            vars.var(&name.to_uppercase())
+                .or_else(|_| vars.var(name))
+                .ok()
+                .filter(|value| !value.is_empty())
+        };
+
+        let config = Self {
+            http_proxy: var("http_proxy"),
+            https_proxy: var("https_proxy"),
+            no_proxy: var("no_proxy"),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Validate that the configured proxies are URLs
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, proxy) in [
+            ("HTTP_PROXY", &self.http_proxy),
+            ("HTTPS_PROXY", &self.https_proxy),
+        ] {
+            if let Some(proxy) = proxy {
+                let url = reqwest::Url::parse(proxy)
+                    .map_err(|e| format!("Invalid {} '{}': {}", name, proxy, e))?;
+                if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+                    return Err(format!(
+                        "Unsupported {} scheme '{}' (expected http, https, socks5 or socks5h)",
+                        name,
+                        url.scheme()
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hosts that bypass the proxy: the configured ones, plus loopback if a proxy is set
+    pub fn effective_no_proxy(&self) -> Option<String> {
+        if self.http_proxy.is_none() && self.https_proxy.is_none() {
+            return self.no_proxy.clone();
+        }
+        let mut hosts: Vec<&str> = self
+            .no_proxy
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .collect();
+        for host in LOOPBACK_HOSTS {
+            if !hosts.contains(host) {
+                hosts.push(host);
+            }
+        }
+        Some(hosts.join(","))
+    }
+
+    /// Environment variables to pass to spawned commands
+    ///
+    /// Both the upper- and lowercase forms are set since tools disagree on which they read,
+    /// along with the npm-specific variables used by npm and yarn.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let mut vars = Vec::new();
+        let no_proxy = self.effective_no_proxy();
+        for (name, value) in [
+            ("http_proxy", &self.http_proxy),
+            ("https_proxy", &self.https_proxy),
+            ("no_proxy", &no_proxy),
+        ] {
+            if let Some(value) = value {
+                vars.push((name.to_string(), value.clone()));
+                vars.push((name.to_uppercase(), value.clone()));
+            }
+        }
+        if let Some(value) = &self.http_proxy {
+            vars.push(("npm_config_proxy".to_string(), value.clone()));
+        }
+        if let Some(value) = &self.https_proxy {
+            vars.push(("npm_config_https_proxy".to_string(), value.clone()));
+        }
+
+        vars
+    }
+
+    /// Build an HTTP client that routes through the configured proxies
+    ///
+    /// Requests time out after [`HTTP_REQUEST_TIMEOUT`].
+    pub fn http_client(&self) -> Result<reqwest::Client, String> {
+        let no_proxy = self
+            .effective_no_proxy()
+            .as_deref()
+            .and_then(reqwest::NoProxy::from_string);
+        let mut builder = reqwest::Client::builder()
+            .timeout(HTTP_REQUEST_TIMEOUT)
+            .connect_timeout(HTTP_CONNECT_TIMEOUT);
+
+        if let Some(proxy) = &self.http_proxy {
+            let proxy = reqwest::Proxy::http(proxy)
+                .map_err(|e| format!("Invalid HTTP proxy: {}", e))?
+                .no_proxy(no_proxy.clone());
+            builder = builder.proxy(proxy);
+        }
+        if let Some(proxy) = &self.https_proxy {
+            let proxy = reqwest::Proxy::https(proxy)
+                .map_err(|e| format!("Invalid HTTPS proxy: {}", e))?
+                .no_proxy(no_proxy);
+            builder = builder.proxy(proxy);
+        }
+
+        builder
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))
+    }
+}
+
+impl Default for DeploymentConfig {
+    fn default() -> Self {
+        Self {
+            settlement_layer: SettlementLayer::default(),
+            launch_anvil: false,
+            anvil_fork_url: None,
+            s3_endpoint_url: None,
+            tangle_rpc_url: default_tangle_rpc_url(),
+            working_dir: default_working_dir(),
+            rpc_probe_attempts: default_rpc_probe_attempts(),
+            rpc_probe_interval_secs: default_rpc_probe_interval_secs(),
+            proxy: ProxyConfig::default(),
+            force_redeploy: false,
+            deployment_timeout_secs: default_deployment_timeout_secs(),
+            health_wait_secs: default_health_wait_secs(),
+            compose_down_timeout_secs: default_compose_down_timeout_secs(),
+            image_pull_timeout_secs: default_image_pull_timeout_secs(),
+            step_timeouts: StepTimeouts::default(),
+            compose_project_name: None,
+            compose_override_file: None,
+            prewarm_max_age_secs: default_prewarm_max_age_secs(),
+            git_clone_depth: default_git_clone_depth(),
+            git_sparse_checkout: false,
+            artifact_cache_dir: None,
+            max_batch_lag_secs: default_max_batch_lag_secs(),
+            stop_order: default_stop_order(),
+            extra_env: BTreeMap::new(),
+            parent_chain_fallback_rpcs: Vec::new(),
+            resume_interrupted: default_resume_interrupted(),
+            ports: PortAllocation::default(),
+            read_replica: false,
+            metrics_bind_addr: default_diagnostics_bind_addr(),
+            pprof_bind_addr: default_diagnostics_bind_addr(),
+            profile: DeploymentProfile::default(),
+            min_cpus: default_min_cpus(),
+            min_memory_gb: default_min_memory_gb(),
+            min_disk_gb: default_min_disk_gb(),
+            avail_api_url: default_avail_api_url(),
+            status_path: None,
+            status_flush_interval_secs: default_status_flush_interval_secs(),
+            execution_mode: ExecutionMode::default(),
+            node_image: default_node_image(),
+            toolchain_image: default_toolchain_image(),
+            package_manager: PackageManager::default(),
+            max_captured_output_bytes: default_max_captured_output_bytes(),
+        }
+    }
+}
+
+/// Default docker compose project name for a rollup
+pub fn default_compose_project_name(chain_id: ChainId) -> String {
+    format!("orbit-{}", chain_id)
+}
+
+/// S3 fallback object prefix used when none is configured, unique to the chain so
+/// rollups sharing a bucket don't collide
+pub fn default_s3_object_prefix(chain_id: ChainId) -> String {
+    format!("orbit/{}/", chain_id)
+}
+
+/// Metrics and pprof are only published on loopback unless the operator opts in
+fn default_diagnostics_bind_addr() -> IpAddr {
+    IpAddr::V4(Ipv4Addr::LOCALHOST)
+}
+
+fn default_min_cpus() -> usize {
+    4
+}
+
+fn default_min_memory_gb() -> u64 {
+    8
+}
+
+fn default_min_disk_gb() -> u64 {
+    20
+}
+
+/// Default Avail endpoint, on the Turing testnet
+fn default_avail_api_url() -> String {
+    "https://turing-rpc.avail.so/rpc".to_string()
+}
+
+fn default_status_flush_interval_secs() -> u64 {
+    5
+}
+
+/// Default Nitro node image
+fn default_node_image() -> String {
+    crate::deployment::DOCKER_IMAGE.to_string()
+}
+
+/// Default image for containerized script execution, pinned to a Node.js LTS release
+fn default_toolchain_image() -> String {
+    "node:20.18.0-bookworm".to_string()
+}
+
+fn default_git_clone_depth() -> u32 {
+    1
+}
+
+fn default_prewarm_max_age_secs() -> u64 {
+    60 * 60
+}
+
+fn default_resume_interrupted() -> bool {
+    true
+}
+
+/// Explorer front ends first, then the explorer, the node, and finally the data stores
+/// everything else depends on
+fn default_stop_order() -> Vec<String> {
+    [
+        "proxy",
+        "frontend",
+        "visualizer",
+        "stats",
+        "blockscout",
+        "nitro",
+        "redis",
+        "postgres",
+        "db",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// Twice the batch poster's default maximum batch delay of one hour
+fn default_max_batch_lag_secs() -> u64 {
+    2 * 60 * 60
+}
+
+fn default_health_wait_secs() -> u64 {
+    5 * 60
+}
+
+fn default_max_captured_output_bytes() -> usize {
+    4 * 1024 * 1024
+}
+
+fn default_compose_down_timeout_secs() -> u64 {
+    30
+}
+
+fn default_tangle_rpc_url() -> String {
+    "http://127.0.0.1:9944".to_string()
+}
+
+fn default_image_pull_timeout_secs() -> u64 {
+    15 * 60
+}
+
+fn default_deployment_timeout_secs() -> u64 {
+    30 * 60
+}
+
+fn default_rpc_probe_attempts() -> u32 {
+    30
+}
+
+fn default_rpc_probe_interval_secs() -> u64 {
+    2
+}
+
+/// Resolve a configured path against the current directory
+///
+/// Docker bind mounts, such as the working directory mount in
+/// [`ExecutionMode::Container`], require absolute host paths.
+fn absolute_path(name: &str, path: &str) -> Result<PathBuf, String> {
+    std::path::absolute(path).map_err(|e| format!("Invalid {} '{}': {}", name, path, e))
+}
+
+/// Default working directory, under the OS temp dir
+fn default_working_dir() -> PathBuf {
+    env::temp_dir().join("orbit-deployment")
+}
+
+impl DeploymentConfig {
+    /// Load deployment settings from environment variables, using defaults for unset values
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_vars(&EnvVars::default())
+    }
+
+    /// Load deployment settings from `vars`, using defaults for unset values
+    pub fn from_vars(vars: &EnvVars) -> Result<Self, String> {
+        let mut config = Self {
+            proxy: ProxyConfig::from_vars(vars)?,
+            ..Self::default()
+        };
+
+        if let Ok(layer) = vars.var("SETTLEMENT_LAYER") {
+            config.settlement_layer = layer.parse()?;
+        }
+        if let Ok(launch) = vars.var("LAUNCH_ANVIL") {
+            config.launch_anvil = launch.to_lowercase() == "true";
+        }
+        if let Ok(url) = vars.var("ANVIL_FORK_URL") {
+            config.anvil_fork_url = Some(url).filter(|url| !url.is_empty());
+        }
+        // Shared with the blueprint runner, which reads the same variable
+        if let Some(url) = vars.var("HTTP_RPC_URL").ok().filter(|url| !url.is_empty()) {
+            validate_url(&url).map_err(|e| format!("Invalid HTTP_RPC_URL: {}", e))?;
+            config.tangle_rpc_url = url;
+        }
+        if let Some(url) = vars
+            .var("S3_ENDPOINT_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+        {
+            validate_url(&url).map_err(|e| format!("Invalid S3_ENDPOINT_URL: {}", e))?;
+            config.s3_endpoint_url = Some(url);
+        }
+        if let Ok(dir) = vars.var("WORKING_DIR") {
+            config.working_dir = absolute_path("WORKING_DIR", &dir)?;
+        }
+        if let Ok(force) = vars.var("FORCE_REDEPLOY") {
+            config.force_redeploy = force.to_lowercase() == "true";
+        }
+        if let Ok(timeout) = vars.var("DEPLOYMENT_TIMEOUT_SECS") {
+            config.deployment_timeout_secs = timeout
+                .parse()
+                .map_err(|e| format!("Invalid DEPLOYMENT_TIMEOUT_SECS: {}", e))?;
+        }
+        if let Ok(name) = vars.var("COMPOSE_PROJECT_NAME") {
+            config.compose_project_name = Some(name).filter(|name| !name.is_empty());
+        }
+        if let Some(path) = vars
+            .var("COMPOSE_OVERRIDE_FILE")
+            .ok()
+            .filter(|path| !path.is_empty())
+        {
+            let path = absolute_path("COMPOSE_OVERRIDE_FILE", &path)?;
+            if !path.is_file() {
+                return Err(format!(
+                    "COMPOSE_OVERRIDE_FILE {} does not exist",
+                    path.display()
+                ));
+            }
+            config.compose_override_file = Some(path);
+        }
+        if let Ok(wait) = vars.var("HEALTH_WAIT_SECS") {
+            config.health_wait_secs = wait
+                .parse()
+                .map_err(|e| format!("Invalid HEALTH_WAIT_SECS: {}", e))?;
+        }
+        if let Ok(timeout) = vars.var("COMPOSE_DOWN_TIMEOUT_SECS") {
+            config.compose_down_timeout_secs = timeout
+                .parse()
+                .map_err(|e| format!("Invalid COMPOSE_DOWN_TIMEOUT_SECS: {}", e))?;
+        }
+        if let Ok(timeout) = vars.var("IMAGE_PULL_TIMEOUT_SECS") {
+            config.image_pull_timeout_secs = timeout
+                .parse()
+                .map_err(|e| format!("Invalid IMAGE_PULL_TIMEOUT_SECS: {}", e))?;
+        }
+        config.step_timeouts.load_env()?;
+        if let Ok(offset) = vars.var("PORT_OFFSET") {
+            let offset = offset
+                .parse()
+                .map_err(|e| format!("Invalid PORT_OFFSET: {}", e))?;
+            config.ports = PortAllocation::default().with_offset(offset)?;
+        }
+        if let Ok(addr) = vars.var("METRICS_BIND_ADDR") {
+            config.metrics_bind_addr = addr
+                .parse()
+                .map_err(|e| format!("Invalid METRICS_BIND_ADDR: {}", e))?;
+        }
+        if let Ok(addr) = vars.var("PPROF_BIND_ADDR") {
+            config.pprof_bind_addr = addr
+                .parse()
+                .map_err(|e| format!("Invalid PPROF_BIND_ADDR: {}", e))?;
+        }
+        if let Ok(profile) = vars.var("DEPLOYMENT_PROFILE") {
+            config.profile = profile.parse()?;
+        }
+        if let Ok(cpus) = vars.var("MIN_CPUS") {
+            config.min_cpus = cpus
+                .parse()
+                .map_err(|e| format!("Invalid MIN_CPUS: {}", e))?;
+        }
+        if let Ok(memory) = vars.var("MIN_MEMORY_GB") {
+            config.min_memory_gb = memory
+                .parse()
+                .map_err(|e| format!("Invalid MIN_MEMORY_GB: {}", e))?;
+        }
+        if let Ok(disk) = vars.var("MIN_DISK_GB") {
+            config.min_disk_gb = disk
+                .parse()
+                .map_err(|e| format!("Invalid MIN_DISK_GB: {}", e))?;
+        }
+        if let Ok(url) = vars.var("AVAIL_API_URL") {
+            validate_url(&url).map_err(|e| format!("Invalid AVAIL_API_URL: {}", e))?;
+            config.avail_api_url = url;
+        }
+        if let Ok(path) = vars.var("STATUS_PATH") {
+            if !path.is_empty() {
+                config.status_path = Some(absolute_path("STATUS_PATH", &path)?);
+            }
+        }
+        if let Ok(interval) = vars.var("STATUS_FLUSH_INTERVAL_SECS") {
+            config.status_flush_interval_secs = interval
+                .parse()
+                .map_err(|e| format!("Invalid STATUS_FLUSH_INTERVAL_SECS: {}", e))?;
+        }
+        if let Ok(mode) = vars.var("EXECUTION_MODE") {
+            config.execution_mode = mode.parse()?;
+        }
+        if let Ok(manager) = vars.var("PACKAGE_MANAGER") {
+            config.package_manager = manager.parse()?;
+        }
+        if let Ok(max_bytes) = vars.var("MAX_CAPTURED_OUTPUT_BYTES") {
+            config.max_captured_output_bytes = max_bytes
+                .parse()
+                .map_err(|e| format!("Invalid MAX_CAPTURED_OUTPUT_BYTES: {}", e))?;
+        }
+        if let Ok(image) = vars.var("NODE_IMAGE") {
+            let image = image.trim();
+            if !image.is_empty() {
+                if image.chars().any(char::is_whitespace) {
+                    return Err(format!("Invalid NODE_IMAGE: {}", image));
+                }
+                config.node_image = image.to_string();
+            }
+        }
+        if let Ok(image) = vars.var("TOOLCHAIN_IMAGE") {
+            if !image.is_empty() {
+                config.toolchain_image = image;
+            }
+        }
+        if let Ok(depth) = vars.var("GIT_CLONE_DEPTH") {
+            config.git_clone_depth = depth
+                .parse()
+                .map_err(|e| format!("Invalid GIT_CLONE_DEPTH: {}", e))?;
+        }
+        if let Ok(sparse) = vars.var("GIT_SPARSE_CHECKOUT") {
+            config.git_sparse_checkout = sparse.to_lowercase() == "true";
+        }
+        if let Ok(dir) = vars.var("ARTIFACT_CACHE_DIR") {
+            if !dir.is_empty() {
+                config.artifact_cache_dir = Some(absolute_path("ARTIFACT_CACHE_DIR", &dir)?);
+            }
+        }
+        if let Ok(lag) = vars.var("MAX_BATCH_LAG_SECS") {
+            config.max_batch_lag_secs = lag
+                .parse()
+                .map_err(|e| format!("Invalid MAX_BATCH_LAG_SECS: {}", e))?;
+        }
+        if let Ok(order) = vars.var("STOP_ORDER") {
+            config.stop_order = order
+                .split(',')
+                .map(|service| service.trim().to_string())
+                .filter(|service| !service.is_empty())
+                .collect();
+        }
+        for (name, value) in vars.vars() {
+            if let Some(name) = name.strip_prefix(SCRIPT_ENV_PREFIX) {
+                config.extra_env.insert(name.to_string(), value);
+            }
+        }
+        if let Ok(rpcs) = vars.var("PARENT_CHAIN_FALLBACK_RPCS") {
+            config.parent_chain_fallback_rpcs = rpcs
+                .split(',')
+                .map(|rpc| rpc.trim().to_string())
+                .filter(|rpc| !rpc.is_empty())
+                .collect();
+        }
+        if let Ok(replica) = vars.var("READ_REPLICA") {
+            config.read_replica = replica.to_lowercase() == "true";
+        }
+        if let Ok(resume) = vars.var("RESUME_INTERRUPTED_DEPLOY") {
+            config.resume_interrupted = resume.to_lowercase() != "false";
+        }
+        if let Ok(age) = vars.var("PREWARM_MAX_AGE_SECS") {
+            config.prewarm_max_age_secs = age
+                .parse()
+                .map_err(|e| format!("Invalid PREWARM_MAX_AGE_SECS: {}", e))?;
+        }
+        if let Ok(attempts) = vars.var("RPC_PROBE_ATTEMPTS") {
+            config.rpc_probe_attempts = attempts
+                .parse()
+                .map_err(|e| format!("Invalid RPC_PROBE_ATTEMPTS: {}", e))?;
+        }
+        if let Ok(interval) = vars.var("RPC_PROBE_INTERVAL_SECS") {
+            config.rpc_probe_interval_secs = interval
+                .parse()
+                .map_err(|e| format!("Invalid RPC_PROBE_INTERVAL_SECS: {}", e))?;
+        }
+
+        config.validate_exposure()?;
+        config.validate_extra_env()?;
+        config.validate_package_manager()?;
+        config.validate_parent_chain_fallbacks()?;
+        config.validate_local_fork()?;
+        Ok(config)
+    }
+
+    /// Check that extra script variables have valid names and don't override reserved ones
+    pub fn validate_extra_env(&self) -> Result<(), String> {
+        for name in self.extra_env.keys() {
+            let valid = !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(format!(
+                    "Invalid script environment variable name '{}'",
+                    name
+                ));
+            }
+            if RESERVED_SCRIPT_ENV.contains(&name.to_uppercase().as_str()) {
+                return Err(format!(
+                    "Script environment variable {} is set by the service and can't be overridden",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the package manager can run where the deploy scripts are executed
+    ///
+    /// Container mode runs the scripts in the toolchain image, which only has npm and yarn.
+    pub fn validate_package_manager(&self) -> Result<(), String> {
+        if self.execution_mode == ExecutionMode::Container && !self.package_manager.in_node_image()
+        {
+            return Err(format!(
+                "PACKAGE_MANAGER={} is not available in the toolchain image; use npm or yarn \
+                 with EXECUTION_MODE=container",
+                self.package_manager
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that the working directory exists, or can be created, and is writable
+    pub fn validate_working_dir(&self) -> Result<(), String> {
+        create_private_dir(&self.working_dir).map_err(|e| {
+            format!(
+                "WORKING_DIR {} is not a writable directory: {}",
+                self.working_dir.display(),
+                e
+            )
+        })
+    }
+
+    /// Check that the anvil settings are only used in local fork mode and are valid
+    pub fn validate_local_fork(&self) -> Result<(), String> {
+        if self.launch_anvil && self.settlement_layer != SettlementLayer::LocalFork {
+            return Err("LAUNCH_ANVIL requires SETTLEMENT_LAYER=local-fork".to_string());
+        }
+        if let Some(url) = &self.anvil_fork_url {
+            validate_url(url).map_err(|e| format!("Invalid ANVIL_FORK_URL: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Check that every fallback parent chain RPC is a valid URL
+    pub fn validate_parent_chain_fallbacks(&self) -> Result<(), String> {
+        for rpc in &self.parent_chain_fallback_rpcs {
+            validate_url(rpc).map_err(|e| format!("Invalid fallback parent chain RPC: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Parent chain RPCs in failover order: `primary`, then the distinct fallbacks
+    pub fn parent_chain_rpcs(&self, primary: &str) -> Vec<String> {
+        let mut rpcs = vec![primary.to_string()];
+        for rpc in &self.parent_chain_fallback_rpcs {
+            if !rpcs.contains(rpc) {
+                rpcs.push(rpc.clone());
+            }
+        }
+        rpcs
+    }
+
+    /// Extra script variables for logging, with secret-looking values redacted
+    pub fn extra_env_summary(&self) -> String {
+        self.extra_env
+            .iter()
+            .map(|(name, value)| {
+                let value = if is_secret_key(name) {
+                    REDACTED
+                } else {
+                    value.as_str()
+                };
+                format!("{}={}", name, value)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Position of a compose service in the stop order
+    ///
+    /// A service matching several patterns stops at the latest one, so e.g. `stats-db`
+    /// stops with the data stores rather than with `stats`. Services matching no pattern
+    /// stop first.
+    pub fn stop_rank(&self, service: &str) -> usize {
+        let service = service.to_lowercase();
+        self.stop_order
+            .iter()
+            .rposition(|pattern| service.contains(&pattern.to_lowercase()))
+            .map_or(0, |position| position + 1)
+    }
+
+    /// Warnings for node diagnostics endpoints published on every network interface
+    pub fn exposure_warnings(&self) -> Vec<String> {
+        [
+            ("metrics", self.metrics_bind_addr),
+            ("pprof", self.pprof_bind_addr),
+        ]
+        .into_iter()
+        .filter(|(_, addr)| addr.is_unspecified())
+        .map(|(name, addr)| {
+            format!(
+                "Node {} is published on {}, exposing it to the network; bind it to 127.0.0.1 instead",
+                name, addr
+            )
+        })
+        .collect()
+    }
+
+    /// Refuse publicly exposed diagnostics endpoints in the production profile
+    pub fn validate_exposure(&self) -> Result<(), String> {
+        let warnings = self.exposure_warnings();
+        if self.profile == DeploymentProfile::Production && !warnings.is_empty() {
+            return Err(warnings.join("; "));
+        }
+        Ok(())
+    }
+
+    /// Docker compose project name for the rollup with the given chain ID
+    pub fn compose_project_name(&self, chain_id: ChainId) -> String {
+        self.compose_project_name
+            .clone()
+            .unwrap_or_else(|| default_compose_project_name(chain_id))
+    }
+
+    /// File the deployment status is persisted to
+    pub fn status_path(&self) -> PathBuf {
+        self.status_path
+            .clone()
+            .unwrap_or_else(|| self.working_dir.join("deployment-status.json"))
+    }
+
+    /// File the deployment event log is persisted to, next to the status file
+    pub fn events_path(&self) -> PathBuf {
+        self.status_path().with_file_name("deployment-events.jsonl")
+    }
+
+    /// Interval between periodic status flushes
+    pub fn status_flush_interval(&self) -> Duration {
+        Duration::from_secs(self.status_flush_interval_secs)
+    }
+
+    /// How long a prewarm stays fresh
+    pub fn prewarm_max_age(&self) -> Duration {
+        Duration::from_secs(self.prewarm_max_age_secs)
+    }
+
+    /// Overall deployment time budget
+    pub fn deployment_timeout(&self) -> Duration {
+        Duration::from_secs(self.deployment_timeout_secs)
+    }
+
+    /// Time containers get to stop on `docker compose down` before being killed
+    pub fn compose_down_timeout(&self) -> Duration {
+        Duration::from_secs(self.compose_down_timeout_secs)
+    }
+
+    /// Time the node image pull may take before it is aborted
+    pub fn image_pull_timeout(&self) -> Duration {
+        Duration::from_secs(self.image_pull_timeout_secs)
+    }
+
+    /// Interval between RPC readiness attempts
+    pub fn rpc_probe_interval(&self) -> Duration {
+        Duration::from_secs(self.rpc_probe_interval_secs)
+    }
+}
+
+/// The parent chain topology the rollup settles to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettlementLayer {
+    /// An L2 settling directly on Ethereum L1
+    Ethereum,
+    /// An L3 settling on an Arbitrum L2
+    #[default]
+    Arbitrum,
+    /// A local or forked development chain such as anvil, for local development and CI
+    #[serde(rename = "local-fork")]
+    LocalFork,
+}
+
+impl SettlementLayer {
+    /// Chain IDs of the parent chains supported for this settlement layer
+    pub fn parent_chain_ids(&self) -> &'static [u64] {
+        match self {
+            // Mainnet, Sepolia, Holesky
+            SettlementLayer::Ethereum => &[1, 11_155_111, 17_000],
+            // Arbitrum One, Arbitrum Nova, Arbitrum Sepolia
+            SettlementLayer::Arbitrum => &[42_161, 42_170, 421_614],
+            // Any chain, since a fork keeps the chain ID of the chain it forks
+            SettlementLayer::LocalFork => &[],
+        }
+    }
+
+    /// Network name passed to the contract deploy script for the given parent chain
+    ///
+    /// A local fork of a known chain deploys as that chain, so the deploy script finds the
+    /// forked chain's contracts; any other local chain deploys to `localhost`.
+    pub fn network_name(&self, parent_chain_id: u64) -> Option<&'static str> {
+        match (self, parent_chain_id) {
+            (SettlementLayer::LocalFork, _) => SettlementLayer::Ethereum
+                .network_name(parent_chain_id)
+                .or_else(|| SettlementLayer::Arbitrum.network_name(parent_chain_id))
+                .or(Some("localhost")),
+            (SettlementLayer::Ethereum, 1) => Some("mainnet"),
+            (SettlementLayer::Ethereum, 11_155_111) => Some("sepolia"),
+            (SettlementLayer::Ethereum, 17_000) => Some("holesky"),
+            (SettlementLayer::Arbitrum, 42_161) => Some("arbOne"),
+            (SettlementLayer::Arbitrum, 42_170) => Some("arbNova"),
+            (SettlementLayer::Arbitrum, 421_614) => Some("arbSepolia"),
+            _ => None,
+        }
+    }
+
+    /// Check that a parent chain ID belongs to this settlement layer
+    pub fn validate_parent_chain_id(&self, parent_chain_id: u64) -> Result<(), String> {
+        if *self == SettlementLayer::LocalFork || self.parent_chain_ids().contains(&parent_chain_id)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Parent chain ID {} is not a supported {} settlement chain (expected one of {:?})",
+                parent_chain_id,
+                self,
+                self.parent_chain_ids()
+            ))
+        }
+    }
+}
+
+impl std::str::FromStr for SettlementLayer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ethereum" | "eth" | "l1" => Ok(SettlementLayer::Ethereum),
+            "arbitrum" | "arb" | "l2" => Ok(SettlementLayer::Arbitrum),
+            "local-fork" | "localfork" | "local" | "anvil" => Ok(SettlementLayer::LocalFork),
+            other => Err(format!("Unknown settlement layer: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for SettlementLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettlementLayer::Ethereum => write!(f, "ethereum"),
+            SettlementLayer::Arbitrum => write!(f, "arbitrum"),
+            SettlementLayer::LocalFork => write!(f, "local-fork"),
+        }
+    }
+}
+
+/// Port a launched anvil listens on, taken from the parent chain RPC URL
+///
+/// The URL must point at this host, since that is where the anvil container runs.
+pub fn anvil_port(parent_chain_rpc: &str) -> Result<u16, String> {
+    let url = reqwest::Url::parse(parent_chain_rpc)
+        .map_err(|e| format!("Invalid parent chain RPC: {}", e))?;
+    match url.host_str() {
+        Some("localhost" | "127.0.0.1" | "[::1]") => {}
+        _ => {
+            return Err(format!(
+                "With LAUNCH_ANVIL the parent chain RPC must point at this host, got {}",
+                redact_url(parent_chain_rpc)
+            ));
+        }
+    }
+    url.port_or_known_default().ok_or_else(|| {
+        format!(
+            "Parent chain RPC {} has no port",
+            redact_url(parent_chain_rpc)
+        )
+    })
+}
+
+/// Combine the outcomes of independent checks, reporting every failure
+///
+/// A single failure is returned unchanged; several are joined with `; `.
+fn collect_errors(results: impl IntoIterator<Item = Result<(), String>>) -> Result<(), String> {
+    let errors: Vec<String> = results.into_iter().filter_map(Result::err).collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
 }
 
 /// Configuration for deploying an Avail Orbit rollup
@@ -39,15 +1483,59 @@ pub struct AvailOrbitConfig {
     operator_config: OperatorConfig,
     /// Public rollup metadata
     metadata: RollupMetadata,
+    /// Validated Avail app ID parsed from the metadata
+    avail_app_id: AvailAppId,
 }
 
 impl AvailOrbitConfig {
     /// Create a new config by combining operator config with rollup metadata
-    pub fn new(operator_config: OperatorConfig, metadata: RollupMetadata) -> Self {
-        Self {
+    ///
+    /// Fails if a private key is a placeholder or test key, the metadata's Avail app ID is
+    /// not a non-zero integer, its chain ID belongs to a well-known public chain, or the
+    /// parent chain RPC, rollup RPC endpoint or explorer URL is not a valid URL. On a local
+    /// fork, unset keys default to funded anvil accounts.
+    ///
+    /// Every check runs, and all failures are reported together, so a bad config can be
+    /// fixed in one pass rather than one error per deploy attempt.
+    pub fn new(
+        mut operator_config: OperatorConfig,
+        mut metadata: RollupMetadata,
+    ) -> Result<Self, String> {
+        operator_config.fill_local_fork_keys();
+        metadata.normalize_urls();
+        let avail_app_id = metadata.parse_avail_app_id();
+        let deployment = &operator_config.deployment;
+        collect_errors([
+            operator_config.validate_keys(),
+            avail_app_id.as_ref().map(|_| ()).map_err(|e| e.clone()),
+            metadata.chain_id.check_collision(),
+            metadata.validate_labels(),
+            metadata.validate_urls(),
+            deployment.validate_exposure(),
+            deployment.validate_extra_env(),
+            deployment.validate_package_manager(),
+            deployment.validate_parent_chain_fallbacks(),
+            deployment.validate_local_fork(),
+            validate_url(&metadata.parent_chain_rpc)
+                .map_err(|e| format!("Invalid parent chain RPC: {}", e)),
+            if deployment.launch_anvil {
+                anvil_port(&metadata.parent_chain_rpc).map(|_| ())
+            } else {
+                Ok(())
+            },
+            if metadata.fallback_s3_enable {
+                validate_s3_prefix(&operator_config.fallback_s3_object_prefix(metadata.chain_id))
+            } else {
+                Ok(())
+            },
+        ])?;
+        let avail_app_id = avail_app_id?;
+
+        Ok(Self {
             operator_config,
             metadata,
-        }
+            avail_app_id,
+        })
     }
 
     /// Generate environment content for this configuration
@@ -73,7 +1561,7 @@ impl AvailOrbitConfig {
             "AVAIL_ADDR_SEED={}\n",
             self.operator_config.avail_addr_seed
         ));
-        content.push_str(&format!("AVAIL_APP_ID={}\n", self.metadata.avail_app_id));
+        content.push_str(&format!("AVAIL_APP_ID={}\n", self.avail_app_id));
 
         // Add S3 fallback if enabled
         content.push_str(&format!(
@@ -90,9 +1578,10 @@ impl AvailOrbitConfig {
             if let Some(val) = &self.operator_config.fallback_s3_region {
                 content.push_str(&format!("FALLBACKS3_REGION={}\n", val));
             }
-            if let Some(val) = &self.operator_config.fallback_s3_object_prefix {
-                content.push_str(&format!("FALLBACKS3_OBJECT_PREFIX={}\n", val));
-            }
+            content.push_str(&format!(
+                "FALLBACKS3_OBJECT_PREFIX={}\n",
+                self.fallback_s3_object_prefix()
+            ));
             if let Some(val) = &self.operator_config.fallback_s3_bucket {
                 content.push_str(&format!("FALLBACKS3_BUCKET={}\n", val));
             }
@@ -107,14 +1596,41 @@ impl AvailOrbitConfig {
         content
     }
 
+    /// Get the operator's deployment settings
+    pub fn get_deployment_config(&self) -> &DeploymentConfig {
+        &self.operator_config.deployment
+    }
+
+    /// Get the docker compose project name for this rollup
+    pub fn compose_project_name(&self) -> String {
+        self.operator_config
+            .deployment
+            .compose_project_name(self.metadata.chain_id)
+    }
+
+    /// Get the deployment working directory
+    pub fn working_dir(&self) -> &Path {
+        &self.operator_config.deployment.working_dir
+    }
+
     /// Get the deployer private key
     pub fn get_deployer_private_key(&self) -> &str {
         &self.operator_config.deployer_private_key
     }
 
+    /// Get the operator config this was built from
+    pub fn operator_config(&self) -> &OperatorConfig {
+        &self.operator_config
+    }
+
+    /// Get the Avail address seed
+    pub fn get_avail_addr_seed(&self) -> &str {
+        &self.operator_config.avail_addr_seed
+    }
+
     /// Get the Avail app ID
-    pub fn get_avail_app_id(&self) -> &str {
-        &self.metadata.avail_app_id
+    pub fn get_avail_app_id(&self) -> AvailAppId {
+        self.avail_app_id
     }
 
     /// Get the parent chain RPC endpoint
@@ -122,13 +1638,66 @@ impl AvailOrbitConfig {
         &self.metadata.parent_chain_rpc
     }
 
+    /// Get the parent chain RPC endpoint followed by the operator's fallbacks
+    pub fn parent_chain_rpcs(&self) -> Vec<String> {
+        self.operator_config
+            .deployment
+            .parent_chain_rpcs(&self.metadata.parent_chain_rpc)
+    }
+
     /// Check if S3 fallback is enabled
     pub fn is_fallback_s3_enabled(&self) -> bool {
         self.metadata.fallback_s3_enable
     }
 
+    /// Get the S3 fallback object prefix, defaulting to one unique to the chain
+    pub fn fallback_s3_object_prefix(&self) -> String {
+        self.operator_config
+            .fallback_s3_object_prefix(self.metadata.chain_id)
+    }
+
     /// Get the rollup metadata
     pub fn get_metadata(&self) -> &RollupMetadata {
         &self.metadata
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_takes_precedence_over_the_environment() {
+        let vars = EnvVars::with_overlay(BTreeMap::from([
+            ("PATH".to_string(), "/overlay".to_string()),
+            ("PACKAGE_MANAGER".to_string(), "npm".to_string()),
+            ("SCRIPT_ENV_FOO".to_string(), "bar".to_string()),
+        ]));
+        assert_eq!(vars.var("PATH").unwrap(), "/overlay");
+        assert!(vars.vars().contains_key("SCRIPT_ENV_FOO"));
+
+        let config = DeploymentConfig::from_vars(&vars).unwrap();
+        assert_eq!(config.package_manager, PackageManager::Npm);
+        assert_eq!(config.extra_env.get("FOO").map(String::as_str), Some("bar"));
+    }
+
+    #[test]
+    fn loopback_bypasses_the_proxy() {
+        let direct = ProxyConfig::default();
+        assert_eq!(direct.effective_no_proxy(), None);
+
+        let proxied = ProxyConfig {
+            http_proxy: Some("http://proxy.example.com:3128".to_string()),
+            https_proxy: None,
+            no_proxy: Some("internal.example.com, localhost".to_string()),
+        };
+        assert_eq!(
+            proxied.effective_no_proxy().as_deref(),
+            Some("internal.example.com,localhost,127.0.0.1,::1")
+        );
+        assert!(proxied.env_vars().contains(&(
+            "NO_PROXY".to_string(),
+            "internal.example.com,localhost,127.0.0.1,::1".to_string()
+        )));
+    }
+}
@@ -3,360 +3,3327 @@
 //! This module contains the functions for deploying and managing Arbitrum Orbit rollups
 //! with AVAIL data availability.
 
-use crate::config::AvailOrbitConfig;
-use crate::types::{DeploymentStatus, RollupMetadata};
-use std::path::Path;
+use crate::artifact_cache::ArtifactCache;
+use crate::avail::{check_seed_network, verify_app_id};
+use crate::cleanup::mark_working_dir;
+use crate::config::{
+    AvailOrbitConfig, DeploymentConfig, ExecutionMode, PortAllocation, SettlementLayer, anvil_port,
+};
+use crate::metrics::{AvailStats, fetch_avail_stats};
+use crate::preflight::HostResources;
+use crate::rpc::{
+    call_uint, find_batch_containing_block, get_block_number, get_block_timestamp, get_chain_id,
+    has_balance, has_code, probe_rpc_ready, with_failover,
+};
+use crate::types::{
+    BatchLag, ChainInfo, ConfigReload, ContainerRestart, DeployedAddresses, DeploymentPhase,
+    DeploymentStatus, DeploymentStep, RollupMetadata, Teardown,
+};
+use crate::util::{
+    changed_fields, check_docker_daemon_access, check_package_manager_available, combined_output,
+    create_private_dir, docker_error, extract_contract_addresses, is_address,
+    is_docker_permission_denied, is_transient_network_error, normalize_container_ids,
+    output_streaming, parse_ether, redact_secret_values, redact_url, restrict_file_permissions,
+    retry_with_backoff, tail_bytes, write_private_file,
+};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::process::Command as TokioCommand;
+use tokio::sync::mpsc;
 
-const DEPLOYMENT_DIR: &str = "orbit-deployment";
-const DOCKER_IMAGE: &str = "availj/avail-nitro-node:v2.2.1-upstream-v3.2.1";
+/// Nitro node image the rollup runs unless `NODE_IMAGE` overrides it
+pub const DOCKER_IMAGE: &str = "availj/avail-nitro-node:v2.2.1-upstream-v3.2.1";
+/// Repository of the Nitro node images, whose references in the compose file are replaced
+const NODE_IMAGE_REPO: &str = "availj/avail-nitro-node";
+/// Foundry image providing anvil for local fork deployments
+const ANVIL_IMAGE: &str = "ghcr.io/foundry-rs/foundry:v1.0.0";
 const ORBIT_SDK_REPO: &str = "https://github.com/availproject/arbitrum-orbit-sdk.git";
 const ORBIT_SDK_BRANCH: &str = "avail-develop-upstream-v0.20.1";
+/// Parts of the orbit SDK the rollup example needs: the SDK sources it links against
+/// and the example itself. Root files are always checked out.
+const ORBIT_SDK_SPARSE_PATHS: &[&str] = &["src", "examples/create-avail-rollup-eth"];
+/// Contracts the chain can't run without, as named in `orbitSetupScriptConfig.json`
+const REQUIRED_CONTRACTS: &[&str] = &["rollup", "inbox", "sequencerInbox", "bridge"];
+/// Names the sequencer inbox address may be recorded under
+const SEQUENCER_INBOX_NAMES: &[&str] = &["sequencerInbox", "SequencerInbox", "Sequencer Inbox"];
+/// Selector of `SequencerInbox.batchCount()`
+const BATCH_COUNT_SELECTOR: &str = "0x06f13056";
 const SETUP_SCRIPT_REPO: &str = "https://github.com/availproject/orbit-setup-script.git";
+/// Attempts made at cloning repositories and pulling the node image on network failures
+const NETWORK_RETRY_ATTEMPTS: u32 = 3;
+/// Delay before the first network retry, doubled for each later one
+const NETWORK_RETRY_DELAY: Duration = Duration::from_secs(5);
+const BRIDGE_UPDATE_ATTEMPTS: u32 = 3;
+const BRIDGE_UPDATE_BACKOFF: Duration = Duration::from_secs(5);
+/// Maximum bytes of script output kept per deployment step
+const MAX_STEP_OUTPUT_BYTES: usize = 64 * 1024;
+/// Bytes of script output included in step error messages
+const ERROR_OUTPUT_TAIL_BYTES: usize = 2 * 1024;
+/// Time `docker compose down` gets beyond the container stop timeout before it is abandoned
+const COMPOSE_DOWN_GRACE: Duration = Duration::from_secs(30);
+/// Minimum time between status snapshots sent while streaming script output
+const LIVE_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+/// Minimum time between image pull progress lines in the deployment log
+const PULL_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+/// File in the working directory recording when the last prewarm finished
+const PREWARM_MARKER: &str = ".prewarmed";
+/// Upper bound accepted for the batch poster's `max-items` setting
+pub const MAX_BATCH_ITEMS: u64 = 100_000;
+/// Upper bound in wei accepted for the minimum L2 base fee (1000 gwei)
+pub const MAX_MIN_L2_BASE_FEE: u64 = 1_000_000_000_000;
+/// Bounds accepted for the L2 speed limit, in gas per second
+pub const MIN_SPEED_LIMIT: u64 = 100_000;
+pub const MAX_SPEED_LIMIT: u64 = 1_000_000_000;
+/// Bounds accepted for the L2 block gas limit; the minimum still fits a contract deployment
+pub const MIN_BLOCK_GAS_LIMIT: u64 = 1_000_000;
+pub const MAX_BLOCK_GAS_LIMIT: u64 = 1_000_000_000;
+/// ArbOwner precompile, used by the chain owner to tune chain parameters
+const ARB_OWNER_ADDRESS: &str = "0x0000000000000000000000000000000000000070";
+/// ArbGasInfo precompile, used to read back gas pricing parameters
+const ARB_GAS_INFO_ADDRESS: &str = "0x000000000000000000000000000000000000006C";
+/// How long a forced batch post waits for new batches to land on the parent chain
+const FORCE_BATCH_POST_WAIT: Duration = Duration::from_secs(5 * 60);
+/// Interval between batch count checks while waiting for a forced batch post
+const FORCE_BATCH_POST_POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// Compose service of the sequencer node in the orbit setup script's compose file
+const SEQUENCER_SERVICE: &str = "nitro";
+/// Compose service of the read replica node
+const READ_REPLICA_SERVICE: &str = "nitro-replica";
+/// Compose file adding the read replica to the setup script's stack
+const READ_REPLICA_COMPOSE_FILE: &str = "docker-compose.replica.yaml";
+/// Compose file mapping `host.docker.internal` to this host in every service
+const HOST_GATEWAY_COMPOSE_FILE: &str = "docker-compose.host-gateway.yaml";
+/// Name the rollup's containers reach this host under
+const DOCKER_HOST_NAME: &str = "host.docker.internal";
+/// Operator's compose override, copied next to the setup script's compose file
+const COMPOSE_OVERRIDE_FILE: &str = "docker-compose.override.yaml";
+/// Node config of the read replica, relative to the setup script directory
+const READ_REPLICA_NODE_CONFIG: &str = "config/replicaNodeConfig.json";
+/// Lowercased fragments of setup script output meaning the bridge already exists
+const BRIDGE_ALREADY_SET_UP_MARKERS: &[&str] = &[
+    "already deployed",
+    "already been deployed",
+    "already set up",
+    "already initialized",
+];
 
 /// Deploy an Avail Orbit rollup
 ///
 /// This function handles the full deployment of an Arbitrum Orbit rollup with AVAIL DA.
 /// It's designed to be called from the binary, not as a job function.
+///
+/// Every step must finish within the configured deployment timeout. If they don't, any
+/// started containers are torn down and a timeout error is returned. The wait for the
+/// chain to become healthy follows with its own budget, so a slow start isn't rolled back.
 pub async fn deploy_rollup(config: AvailOrbitConfig) -> Result<DeploymentStatus, String> {
+    deploy(config, None).await
+}
+
+/// Deploy an Avail Orbit rollup, sending a status snapshot to `progress` after each step
+///
+/// Behaves like [`deploy_rollup`], but lets callers follow the deployment without polling
+/// shared state. Wrap the receiver in a `ReceiverStream` to consume it as a `Stream`. If
+/// the receiver is dropped, the deployment carries on without reporting progress.
+pub async fn deploy_rollup_with_progress(
+    config: AvailOrbitConfig,
+    progress: mpsc::Sender<DeploymentStatus>,
+) -> Result<DeploymentStatus, String> {
+    deploy(config, Some(&progress)).await
+}
+
+async fn deploy(
+    config: AvailOrbitConfig,
+    progress: Option<&mpsc::Sender<DeploymentStatus>>,
+) -> Result<DeploymentStatus, String> {
+    let timeout = config.get_deployment_config().deployment_timeout();
     let mut status = DeploymentStatus::default();
-    status.metadata = Some(RollupMetadata {
-        name: "orbit-rollup".to_string(),
-        chain_id: 412346,
-        avail_app_id: config.get_avail_app_id().to_string(),
-        parent_chain_rpc: config.get_parent_chain_rpc().to_string(),
-        fallback_s3_enable: config.is_fallback_s3_enabled(),
-        local_rpc_endpoint: "http://localhost:8449".to_string(),
-        explorer_url: "http://localhost:4000".to_string(),
+
+    match tokio::time::timeout(timeout, run_deployment(&config, &mut status, progress)).await {
+        Ok(Ok(())) => {
+            // Bounded by the health wait, which reports an unhealthy rollup instead of failing
+            status.healthy = wait_for_healthy(&config, &mut status).await;
+            send_progress(&status, progress).await;
+            Ok(status)
+        }
+        Ok(Err(e)) => Err(e),
+        Err(_) => {
+            rollback_deployment(&config).await;
+            Err(format!("Deployment timed out after {:?}", timeout))
+        }
+    }
+}
+
+/// Run each deployment step in order, recording progress in `status`
+async fn run_deployment(
+    config: &AvailOrbitConfig,
+    status: &mut DeploymentStatus,
+    progress: Option<&mpsc::Sender<DeploymentStatus>>,
+) -> Result<(), String> {
+    status.metadata = Some(config.get_metadata().clone());
+    status.phase = DeploymentPhase::VerifyingParentChain;
+
+    // Fail early if the host is too small to run the chain or lacks the package manager
+    let deployment = config.get_deployment_config();
+    if deployment.execution_mode == ExecutionMode::Host
+        && !check_package_manager_available(deployment.package_manager)
+            .await
+            .unwrap_or(false)
+    {
+        return Err(format!(
+            "Package manager {} is not available on the host",
+            deployment.package_manager
+        ));
+    }
+    check_docker_daemon_access().await?;
+    HostResources::measure(&deployment.working_dir)
+        .await
+        .check(deployment)?;
+    status.push_log("Host meets CPU, memory and disk requirements".to_string());
+
+    // Step 0: Verify the parent chain matches the settlement layer, launching a local one
+    // first if asked to
+    if deployment.launch_anvil {
+        launch_anvil(config, status).await?;
+    }
+    let network = verify_parent_chain(config, status).await?;
+    if deployment.settlement_layer == SettlementLayer::LocalFork {
+        check_deployer_funded(config, status).await?;
+    }
+    check_avail_network(config, status).await;
+    complete_step(status, DeploymentStep::VerifyParentChain, progress).await;
+
+    // Steps 1 and 2 are skipped if a recent prewarm already did them
+    match prewarm_age(deployment).filter(|age| *age <= deployment.prewarm_max_age()) {
+        Some(age) => {
+            mark_working_dir(&deployment.working_dir)?;
+            status.push_log(format!(
+                "Skipping image pull and repository clone, prewarmed {}s ago",
+                age.as_secs()
+            ));
+            complete_step(status, DeploymentStep::PullDockerImage, progress).await;
+        }
+        None => {
+            // Step 1: Pull Docker image
+            pull_docker_image(deployment, status, progress).await?;
+            complete_step(status, DeploymentStep::PullDockerImage, progress).await;
+
+            // Step 2: Clone and set up repositories
+            step_timeout(
+                deployment,
+                "Cloning repositories",
+                deployment.step_timeouts.clone_secs,
+                "CLONE_TIMEOUT_SECS",
+                clone_repositories(deployment, status),
+            )
+            .await??;
+        }
+    }
+    complete_step(status, DeploymentStep::CloneRepositories, progress).await;
+
+    // Step 3: Create configuration files
+    create_config_files(config, status).await?;
+    complete_step(status, DeploymentStep::CreateConfigFiles, progress).await;
+
+    // Step 4: Deploy rollup contracts
+    deploy_contracts(config, network, status, progress).await?;
+    complete_step(status, DeploymentStep::DeployContracts, progress).await;
+
+    // Step 5: Set up and start the chain
+    step_timeout(
+        deployment,
+        "Starting the chain",
+        deployment.step_timeouts.start_chain_secs,
+        "START_CHAIN_TIMEOUT_SECS",
+        setup_and_start_chain(config, status),
+    )
+    .await??;
+    complete_step(status, DeploymentStep::StartChain, progress).await;
+
+    // Step 6: Deploy token bridge
+    deploy_token_bridge(config, status, progress).await?;
+    complete_step(status, DeploymentStep::DeployTokenBridge, progress).await;
+
+    // A finished deployment is only reported healthy once the chain is serving requests,
+    // which the caller checks after the timed steps
+    status.deployed = true;
+    status.phase = DeploymentPhase::Complete;
+    Ok(())
+}
+
+/// Line handler for [`output_streaming`] that appends script output to the deployment log
+///
+/// Lines are tagged with `label`. With a progress channel, a snapshot is offered at most
+/// every [`LIVE_PROGRESS_INTERVAL`], dropping it if the receiver is behind.
+fn live_log<'a>(
+    status: &'a mut DeploymentStatus,
+    progress: Option<&'a mpsc::Sender<DeploymentStatus>>,
+    label: &'a str,
+) -> impl FnMut(&str) + 'a {
+    let mut last_sent = Instant::now();
+    move |line| {
+        let line = line.trim_end();
+        if line.is_empty() {
+            return;
+        }
+        status.push_log(format!("[{}] {}", label, line));
+        if let Some(progress) = progress {
+            if last_sent.elapsed() >= LIVE_PROGRESS_INTERVAL {
+                let _ = progress.try_send(status.clone());
+                last_sent = Instant::now();
+            }
+        }
+    }
+}
+
+/// Record `step` as completed and report the new status
+async fn complete_step(
+    status: &mut DeploymentStatus,
+    step: DeploymentStep,
+    progress: Option<&mpsc::Sender<DeploymentStatus>>,
+) {
+    status.completed_steps.push(step);
+    if let Some(next) = status.current_step() {
+        status.phase = next.into();
+    }
+    send_progress(status, progress).await;
+}
+
+/// Send a snapshot of `status` to the progress channel, if there is one
+async fn send_progress(
+    status: &DeploymentStatus,
+    progress: Option<&mpsc::Sender<DeploymentStatus>>,
+) {
+    if let Some(progress) = progress {
+        // A closed channel only means the caller stopped listening
+        let _ = progress.send(status.clone()).await;
+    }
+}
+
+/// Wait up to the configured health wait for the rollup's containers to become healthy and
+/// its RPC to respond
+///
+/// Both waits share one deadline, so the RPC probe gets whatever the containers left.
+async fn wait_for_healthy(config: &AvailOrbitConfig, status: &mut DeploymentStatus) -> bool {
+    let deployment = config.get_deployment_config();
+    let interval = deployment.rpc_probe_interval();
+
+    let deadline = Instant::now() + Duration::from_secs(deployment.health_wait_secs);
+    if let Err(e) = wait_for_containers(deployment, &status.container_ids, deadline).await {
+        status.push_log(format!("Rollup deployed but not healthy: {}", e));
+        return false;
+    }
+
+    let client = match deployment.proxy.http_client() {
+        Ok(client) => client,
+        Err(e) => {
+            status.push_log(format!("Unable to check rollup health: {}", e));
+            return false;
+        }
+    };
+
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let attempts = (remaining.as_secs() / interval.as_secs().max(1)).max(1) as u32;
+    let rpc_url = deployment.ports.rpc_url();
+    let probe = probe_rpc_ready(&client, &rpc_url, attempts, interval);
+    let probe = tokio::time::timeout_at(deadline.into(), probe)
+        .await
+        .unwrap_or_else(|_| {
+            Err(format!(
+                "RPC not ready within {}s",
+                deployment.health_wait_secs
+            ))
+        });
+
+    let expected_chain_id = config.get_metadata().chain_id.get();
+    match probe {
+        Ok(chain_id) if chain_id != expected_chain_id => {
+            status.push_log(format!(
+                "Rollup deployed but not healthy: RPC serves chain ID {}, expected {}",
+                chain_id, expected_chain_id
+            ));
+            false
+        }
+        Ok(chain_id) => {
+            status.push_log(format!("Rollup RPC is serving chain ID {}", chain_id));
+            true
+        }
+        Err(e) => {
+            status.push_log(format!("Rollup deployed but not healthy: {}", e));
+            false
+        }
+    }
+}
+
+/// Wait until every container is healthy, or running if its image defines no healthcheck
+///
+/// A running container can still be syncing or crash-looping, so Docker's health status is
+/// preferred when there is one. Gives up early if a container exits, and otherwise reports
+/// each container's last observed status once `deadline` passes.
+async fn wait_for_containers(
+    deployment: &DeploymentConfig,
+    container_ids: &[String],
+    deadline: Instant,
+) -> Result<(), String> {
+    if container_ids.is_empty() {
+        return Ok(());
+    }
+
+    loop {
+        let output = command("docker", deployment)
+            .args([
+                "inspect",
+                "--format",
+                "{{.Id}} {{.State.Status}} {{if .State.Health}}{{.State.Health.Status}}{{end}}",
+            ])
+            .args(container_ids)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run docker inspect: {}", e))?;
+        if !output.status.success() {
+            return Err(docker_error(String::from_utf8_lossy(&output.stderr).trim()));
+        }
+
+        let mut pending = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(id), Some(state)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let id = &id[..id.len().min(12)];
+            match (state, fields.next()) {
+                ("exited" | "dead", _) => {
+                    return Err(format!("Container {} is {}", id, state));
+                }
+                ("running", Some("healthy")) | ("running", None) => {}
+                (state, Some(health)) => pending.push(format!("{} {} ({})", id, state, health)),
+                (state, None) => pending.push(format!("{} {}", id, state)),
+            }
+        }
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            return Err(format!("Containers not healthy: {}", pending.join(", ")));
+        }
+        tokio::time::sleep(deployment.rpc_probe_interval()).await;
+    }
+}
+
+/// Tear down anything a timed-out deployment started
+///
+/// Failures are logged rather than returned since the deployment has already failed.
+async fn rollback_deployment(config: &AvailOrbitConfig) {
+    let setup_dir = setup_dir(config.working_dir());
+    if !setup_dir.join("docker-compose.yaml").exists()
+        && !setup_dir.join("docker-compose.yml").exists()
+    {
+        return;
+    }
+
+    tracing::warn!("Rolling back timed-out deployment");
+    let teardown = compose_down(
+        config.get_deployment_config(),
+        &config.compose_project_name(),
+    )
+    .await;
+    if !teardown.force_removed.is_empty() {
+        tracing::warn!(
+            "Force-removed containers during rollback: {}",
+            teardown.force_removed.join(", ")
+        );
+    }
+    for error in &teardown.errors {
+        tracing::error!(
+            "Failed to stop rollup containers during rollback: {}",
+            error
+        );
+    }
+}
+
+/// Take down the rollup's compose project, keeping its named volumes
+///
+/// Containers get the configured timeout to stop before compose kills them. Containers
+/// of the project still present afterwards, e.g. because compose hung or failed, are
+/// force-removed and reported.
+async fn compose_down(deployment: &DeploymentConfig, project_name: &str) -> Teardown {
+    let timeout = deployment.compose_down_timeout();
+    let mut down = compose(
+        deployment,
+        &setup_dir(&deployment.working_dir),
+        project_name,
+    );
+    down.arg("down")
+        .args(["--timeout", &timeout.as_secs().to_string()])
+        .arg("--remove-orphans")
+        .kill_on_drop(true);
+
+    let mut teardown = Teardown::default();
+    match tokio::time::timeout(timeout + COMPOSE_DOWN_GRACE, down.output()).await {
+        Ok(Ok(output)) if output.status.success() => teardown.compose_down_completed = true,
+        Ok(Ok(output)) => tracing::warn!(
+            "docker compose down failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Ok(Err(e)) => tracing::warn!("Failed to run docker compose down: {}", e),
+        Err(_) => tracing::warn!(
+            "docker compose down did not finish within {:?}",
+            timeout + COMPOSE_DOWN_GRACE
+        ),
+    }
+
+    let filter = format!("label=com.docker.compose.project={}", project_name);
+    match docker_ids(deployment, &["ps", "-aq", "--filter", &filter]).await {
+        Ok(containers) => {
+            for container in containers {
+                match docker_remove(deployment, &["rm", "-f", "-v", &container]).await {
+                    Ok(()) => teardown.force_removed.push(container),
+                    Err(e) => teardown.errors.push(format!("{}: {}", container, e)),
+                }
+            }
+        }
+        Err(e) => teardown.errors.push(e),
+    }
+
+    teardown
+}
+
+/// Run a docker listing command and return the IDs or names it prints, one per line
+async fn docker_ids(deployment: &DeploymentConfig, args: &[&str]) -> Result<Vec<String>, String> {
+    let output = command("docker", deployment)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run docker {}: {}", args.join(" "), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "docker {} failed: {}",
+            args.join(" "),
+            docker_error(String::from_utf8_lossy(&output.stderr).trim())
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Run a docker removal command, failing on a non-zero exit
+async fn docker_remove(deployment: &DeploymentConfig, args: &[&str]) -> Result<(), String> {
+    let output = command("docker", deployment)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run docker: {}", e))?;
+    if !output.status.success() {
+        return Err(docker_error(String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Verify the parent chain RPC belongs to the configured settlement layer
+///
+/// Returns the network name to pass to the contract deploy script.
+async fn verify_parent_chain(
+    config: &AvailOrbitConfig,
+    status: &mut DeploymentStatus,
+) -> Result<&'static str, String> {
+    let settlement_layer = config.get_deployment_config().settlement_layer;
+    let client = config.get_deployment_config().proxy.http_client()?;
+
+    // Every reachable RPC must serve the same chain, or failover would switch chains
+    let mut reported: Option<(u64, String)> = None;
+    let mut errors = Vec::new();
+    for rpc in config.parent_chain_rpcs() {
+        match get_chain_id(&client, &rpc).await {
+            Ok(chain_id) => match &reported {
+                Some((expected, first)) if *expected != chain_id => {
+                    return Err(format!(
+                        "Parent chain RPC {} serves chain ID {}, but {} serves {}",
+                        redact_url(&rpc),
+                        chain_id,
+                        redact_url(first),
+                        expected
+                    ));
+                }
+                Some(_) => {}
+                None => reported = Some((chain_id, rpc)),
+            },
+            Err(e) => {
+                let rpc = redact_url(&rpc);
+                tracing::warn!("Parent chain RPC {} is unreachable: {}", rpc, e);
+                status.push_log(format!(
+                    "WARNING: Parent chain RPC {} is unreachable: {}",
+                    rpc, e
+                ));
+                errors.push(e);
+            }
+        }
+    }
+    let (chain_id, _) = reported
+        .ok_or_else(|| format!("Failed to query parent chain ID: {}", errors.join("; ")))?;
+
+    settlement_layer.validate_parent_chain_id(chain_id)?;
+    let network = settlement_layer
+        .network_name(chain_id)
+        .ok_or_else(|| format!("No deploy network known for chain ID {}", chain_id))?;
+
+    status.push_log(format!(
+        "Verified parent chain {} (chain ID {}) for {} settlement",
+        network, chain_id, settlement_layer
+    ));
+    Ok(network)
+}
+
+/// Start an anvil container serving the parent chain RPC, replacing one left by a previous run
+///
+/// Anvil listens on the port of the parent chain RPC URL and forks `ANVIL_FORK_URL` if set.
+/// Besides loopback, the port is published on the Docker bridge gateway, which the rollup's
+/// containers reach as `host.docker.internal`.
+async fn launch_anvil(
+    config: &AvailOrbitConfig,
+    status: &mut DeploymentStatus,
+) -> Result<(), String> {
+    let deployment = config.get_deployment_config();
+    let port = anvil_port(config.get_parent_chain_rpc())?;
+    let name = format!("{}-anvil", config.compose_project_name());
+    // Nothing to remove is the usual case
+    let _ = docker_remove(deployment, &["rm", "-f", &name]).await;
+
+    let gateway = docker_bridge_gateway(deployment).await?;
+    let mut run = command("docker", deployment);
+    run.args(["run", "-d", "--name", &name]);
+    // The fork URL usually carries an API key and may contain shell metacharacters, so it
+    // is passed through the environment rather than on any command line, and quoted where
+    // the container's shell expands it
+    let mut anvil = "anvil --host 0.0.0.0";
+    if let Some(fork_url) = &deployment.anvil_fork_url {
+        run.env("ANVIL_FORK_URL", fork_url)
+            .args(["-e", "ANVIL_FORK_URL"]);
+        anvil = "anvil --host 0.0.0.0 --fork-url \"$ANVIL_FORK_URL\"";
+    }
+    let output = run
+        .arg("-p")
+        .arg(format!("127.0.0.1:{}:8545", port))
+        .arg("-p")
+        .arg(format!("{}:{}:8545", gateway, port))
+        .args(["--entrypoint", "sh", ANVIL_IMAGE, "-c", anvil])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start anvil: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to start anvil: {}",
+            tail_bytes(&combined_output(&output), ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+
+    let client = deployment.proxy.http_client()?;
+    probe_rpc_ready(
+        &client,
+        config.get_parent_chain_rpc(),
+        deployment.rpc_probe_attempts,
+        deployment.rpc_probe_interval(),
+    )
+    .await
+    .map_err(|e| format!("Anvil did not become ready: {}", e))?;
+
+    status.push_log(format!(
+        "Started anvil container {} on port {}{}",
+        name,
+        port,
+        if deployment.anvil_fork_url.is_some() {
+            ", forking ANVIL_FORK_URL"
+        } else {
+            ""
+        }
+    ));
+    Ok(())
+}
+
+/// Address of the default Docker bridge's gateway, which `host-gateway` resolves to
+async fn docker_bridge_gateway(deployment: &DeploymentConfig) -> Result<IpAddr, String> {
+    let output = command("docker", deployment)
+        .args([
+            "network",
+            "inspect",
+            "bridge",
+            "--format",
+            "{{range .IPAM.Config}}{{.Gateway}} {{end}}",
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run docker network inspect: {}", e))?;
+    check_exit("Inspecting the Docker bridge network", &output)?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .find_map(|gateway| gateway.parse::<IpAddr>().ok().filter(IpAddr::is_ipv4))
+        .ok_or_else(|| "Docker bridge network has no IPv4 gateway".to_string())
+}
+
+/// Check that the deployer account holds funds on the parent chain
+///
+/// Deploying from an unfunded account fails midway, which on a local fork usually means
+/// the deployer key isn't one of the chain's funded accounts.
+async fn check_deployer_funded(
+    config: &AvailOrbitConfig,
+    status: &mut DeploymentStatus,
+) -> Result<(), String> {
+    let deployment = config.get_deployment_config();
+    // The key is passed in the environment so it never appears on the command line
+    let output = command("cast", deployment)
+        .args(["wallet", "address"])
+        .env("ETH_PRIVATE_KEY", config.get_deployer_private_key())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run cast: {}", e))?;
+    if !output.status.success() {
+        return Err("Failed to derive the deployer address from DEPLOYER_PRIVATE_KEY".to_string());
+    }
+    let address = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let client = deployment.proxy.http_client()?;
+    if !has_balance(&client, config.get_parent_chain_rpc(), &address).await? {
+        return Err(format!(
+            "Deployer {} has no funds on the parent chain; use a funded anvil account",
+            address
+        ));
+    }
+    status.push_log(format!("Verified deployer {} is funded", address));
+    Ok(())
+}
+
+/// Warn if the Avail app ID or seed don't match the configured Avail network
+///
+/// A mismatch makes DA submission fail silently, but the check itself depends on the Avail
+/// endpoint being reachable, so it never fails the deployment.
+async fn check_avail_network(config: &AvailOrbitConfig, status: &mut DeploymentStatus) {
+    let deployment = config.get_deployment_config();
+    let result = match deployment.proxy.http_client() {
+        Ok(client) => {
+            verify_app_id(
+                &client,
+                &deployment.avail_api_url,
+                config.get_avail_app_id(),
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    }
+    .and_then(|network| {
+        check_seed_network(config.get_avail_addr_seed(), &network)?;
+        Ok(network)
     });
 
-    // Step 1: Pull Docker image
-    pull_docker_image(&mut status).await?;
+    let message = match result {
+        Ok(network) => format!(
+            "Verified Avail app ID {} on {}",
+            config.get_avail_app_id(),
+            network
+        ),
+        Err(e) => {
+            tracing::warn!("Avail configuration check failed: {}", e);
+            format!("WARNING: Could not confirm Avail configuration: {}", e)
+        }
+    };
+    status.push_log(message);
+}
+
+/// Pull the Avail Nitro Node Docker image
+///
+/// The pull itself is performed by the Docker daemon, which uses its own proxy settings.
+/// Progress is logged periodically, and the pull is aborted if it doesn't finish within
+/// the configured image pull timeout. Pulls failing on a transient network error are
+/// retried with backoff.
+async fn pull_docker_image(
+    deployment: &DeploymentConfig,
+    status: &mut DeploymentStatus,
+    progress: Option<&mpsc::Sender<DeploymentStatus>>,
+) -> Result<(), String> {
+    let timeout = deployment.image_pull_timeout();
+    let image = deployment.node_image.as_str();
+    let mut delay = NETWORK_RETRY_DELAY;
+    let mut attempt = 1;
+
+    // The progress log borrows the status, so this can't go through `retry_with_backoff`
+    let output = loop {
+        let mut pull = command("docker", deployment);
+        pull.args(["pull", image]).kill_on_drop(true);
+        let output = tokio::time::timeout(
+            timeout,
+            output_streaming(
+                &mut pull,
+                deployment.max_captured_output_bytes,
+                pull_progress_log(image, status, progress),
+            ),
+        )
+        .await
+        .map_err(|_| {
+            format!(
+                "Pulling Docker image {} timed out after {:?}; check the registry connection \
+                 or raise IMAGE_PULL_TIMEOUT_SECS",
+                image, timeout
+            )
+        })?
+        .map_err(|e| format!("Failed to pull Docker image: {}", e))?;
+
+        let pull_output = combined_output(&output);
+        if output.status.success()
+            || attempt >= NETWORK_RETRY_ATTEMPTS
+            || !is_transient_network_error(&pull_output)
+        {
+            break output;
+        }
+        status.push_log(format!(
+            "Pulling {} failed (attempt {}/{}), retrying in {:?}: {}",
+            image,
+            attempt,
+            NETWORK_RETRY_ATTEMPTS,
+            delay,
+            tail_bytes(pull_output.trim(), ERROR_OUTPUT_TAIL_BYTES)
+        ));
+        send_progress(status, progress).await;
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    };
+
+    if !output.status.success() {
+        let pull_output = combined_output(&output);
+        if is_docker_permission_denied(&pull_output) {
+            return Err(docker_error(&pull_output));
+        }
+        if is_platform_mismatch(&pull_output) {
+            let error = ImagePlatformError {
+                image: image.to_string(),
+                host_platform: host_platform(),
+                available_platforms: image_platforms(deployment, image).await,
+            };
+            return Err(error.to_string());
+        }
+
+        // A previously pulled image can still be used, e.g. while the registry is unreachable
+        if !local_image_exists(deployment, image).await {
+            return Err(format!(
+                "Failed to pull Docker image {} and no local copy exists: {}",
+                image,
+                tail_bytes(pull_output.trim(), ERROR_OUTPUT_TAIL_BYTES)
+            ));
+        }
+        tracing::warn!(
+            "Failed to pull {}: {}",
+            image,
+            tail_bytes(&pull_output, ERROR_OUTPUT_TAIL_BYTES)
+        );
+        status.push_log(format!(
+            "WARNING: Failed to pull {}, using the local image",
+            image
+        ));
+        return Ok(());
+    }
+
+    status.push_log(format!("Successfully pulled Docker image {}", image));
+    Ok(())
+}
+
+/// Whether `image` is present in the local Docker image store
+async fn local_image_exists(deployment: &DeploymentConfig, image: &str) -> bool {
+    command("docker", deployment)
+        .args(["image", "inspect", image])
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Line handler for [`output_streaming`] that logs `docker pull` progress
+///
+/// Tracks the layers the pull reports and logs how many are done at most every
+/// [`PULL_PROGRESS_INTERVAL`]. Without a terminal `docker pull` doesn't print byte counts,
+/// so progress is reported in layers.
+fn pull_progress_log<'a>(
+    image: &'a str,
+    status: &'a mut DeploymentStatus,
+    progress: Option<&'a mpsc::Sender<DeploymentStatus>>,
+) -> impl FnMut(&str) + 'a {
+    let started = Instant::now();
+    let mut last_logged = Instant::now();
+    // Layer ID to whether it is pulled
+    let mut layers: BTreeMap<String, bool> = BTreeMap::new();
+    move |line| {
+        let Some((layer, state)) = line.trim().split_once(": ") else {
+            return;
+        };
+        if layer.len() != 12 || !layer.chars().all(|c| c.is_ascii_hexdigit()) {
+            return;
+        }
+        let done = matches!(state, "Pull complete" | "Already exists");
+        *layers.entry(layer.to_string()).or_default() |= done;
+
+        if last_logged.elapsed() >= PULL_PROGRESS_INTERVAL {
+            let pulled = layers.values().filter(|done| **done).count();
+            status.push_log(format!(
+                "Pulling {}: {}/{} layers pulled ({}s elapsed)",
+                image,
+                pulled,
+                layers.len(),
+                started.elapsed().as_secs()
+            ));
+            if let Some(progress) = progress {
+                let _ = progress.try_send(status.clone());
+            }
+            last_logged = Instant::now();
+        }
+    }
+}
+
+/// The node image has no build for the host's platform
+#[derive(Clone, Debug)]
+pub struct ImagePlatformError {
+    /// Image that was pulled
+    pub image: String,
+    /// Platform of the host, e.g. `linux/arm64`
+    pub host_platform: String,
+    /// Platforms the image is published for, if they could be listed
+    pub available_platforms: Vec<String>,
+}
+
+impl std::fmt::Display for ImagePlatformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let available = if self.available_platforms.is_empty() {
+            "unknown".to_string()
+        } else {
+            self.available_platforms.join(", ")
+        };
+        write!(
+            f,
+            "Docker image {} has no build for this host's platform {} (available: {}). Run on \
+             a host with a supported architecture, or enable emulation (e.g. qemu binfmt) and \
+             set DOCKER_DEFAULT_PLATFORM to a supported platform",
+            self.image, self.host_platform, available
+        )
+    }
+}
+
+/// Whether `docker pull` output reports that no image exists for the host's platform
+fn is_platform_mismatch(output: &str) -> bool {
+    let output = output.to_lowercase();
+    output.contains("no matching manifest")
+        || output.contains("does not match the specified platform")
+        || output.contains("does not match the detected host platform")
+}
+
+/// Docker platform of the host, e.g. `linux/amd64`
+fn host_platform() -> String {
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "arm" => "arm",
+        other => other,
+    };
+    format!("linux/{}", arch)
+}
+
+/// Platforms a multi-platform image is published for, empty if they can't be listed
+async fn image_platforms(deployment: &DeploymentConfig, image: &str) -> Vec<String> {
+    let Ok(output) = command("docker", deployment)
+        .args(["manifest", "inspect", image])
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    let Ok(manifest) = serde_json::from_slice::<Value>(&output.stdout) else {
+        return Vec::new();
+    };
+
+    manifest["manifests"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let platform = &entry["platform"];
+            let os = platform["os"].as_str()?;
+            let arch = platform["architecture"].as_str()?;
+            // Attestation manifests are listed with an unknown platform
+            if os == "unknown" {
+                return None;
+            }
+            Some(match platform["variant"].as_str() {
+                Some(variant) => format!("{}/{}/{}", os, arch, variant),
+                None => format!("{}/{}", os, arch),
+            })
+        })
+        .collect()
+}
+
+/// Create a command with the operator's proxy settings applied to its environment
+///
+/// Each command runs in its own process group, so [`output_streaming`] can kill the
+/// processes it spawned along with it.
+fn command(program: &str, deployment: &DeploymentConfig) -> TokioCommand {
+    let mut command = TokioCommand::new(program);
+    command.envs(deployment.proxy.env_vars()).kill_on_drop(true);
+    #[cfg(unix)]
+    command.process_group(0);
+    command
+}
+
+/// Run a deployment step, failing if it doesn't finish within `secs`
+///
+/// The error names the step and the `setting` that raises its timeout. The step is
+/// dropped on timeout: commands run through [`output_streaming`] are killed with their
+/// whole process group, and in container mode the toolchain container is removed, since
+/// killing the `docker run` client leaves it running.
+async fn step_timeout<F: Future>(
+    deployment: &DeploymentConfig,
+    step: &str,
+    secs: u64,
+    setting: &str,
+    future: F,
+) -> Result<F::Output, String> {
+    // A container left by a previous process would block the name
+    remove_toolchain_container(deployment).await;
+    let result = tokio::time::timeout(Duration::from_secs(secs), future).await;
+    if result.is_err() {
+        remove_toolchain_container(deployment).await;
+    }
+    result.map_err(|_| {
+        format!(
+            "{} timed out after {}s; check for a hung command or raise {}",
+            step, secs, setting
+        )
+    })
+}
+
+/// Name of the container package manager commands run in, unique per working directory
+///
+/// Deployment steps run one at a time, so one name per working directory is enough.
+fn toolchain_container_name(deployment: &DeploymentConfig) -> String {
+    let mut hasher = DefaultHasher::new();
+    deployment.working_dir.hash(&mut hasher);
+    format!("avail-orbit-toolchain-{:016x}", hasher.finish())
+}
+
+/// Force-remove the toolchain container in container mode, if there is one
+async fn remove_toolchain_container(deployment: &DeploymentConfig) {
+    if deployment.execution_mode != ExecutionMode::Container {
+        return;
+    }
+    let name = toolchain_container_name(deployment);
+    let removed = command("docker", deployment)
+        .args(["rm", "-f", &name])
+        .output()
+        .await;
+    if let Ok(output) = removed {
+        if output.status.success() {
+            tracing::warn!("Removed toolchain container {}", name);
+        }
+    }
+}
+
+/// Create a package manager command running in `dir` with the given extra environment
+///
+/// In [`ExecutionMode::Container`] the package manager runs inside the toolchain image with the working
+/// directory mounted at the same path. The variables are passed by name only, so secrets
+/// never appear in the docker command line.
+fn package_manager(
+    deployment: &DeploymentConfig,
+    dir: &Path,
+    envs: &[(&str, &str)],
+) -> TokioCommand {
+    let program = deployment.package_manager.program();
+    match deployment.execution_mode {
+        ExecutionMode::Host => {
+            let mut manager = command(program, deployment);
+            manager.current_dir(dir).envs(envs.iter().copied());
+            manager
+        }
+        ExecutionMode::Container => {
+            let working_dir = deployment.working_dir.display().to_string();
+            let proxy_vars = deployment.proxy.env_vars();
+
+            let mut docker = command("docker", deployment);
+            docker
+                .envs(envs.iter().copied())
+                .args(["run", "--rm", "--network", "host"])
+                .args(["--name", &toolchain_container_name(deployment)])
+                .args(["-v", &format!("{}:{}", working_dir, working_dir)])
+                .arg("-w")
+                .arg(dir);
+            if let Some(user) = working_dir_owner(&deployment.working_dir) {
+                docker.args(["--user", &user]);
+            }
+            for name in envs
+                .iter()
+                .map(|(name, _)| *name)
+                .chain(proxy_vars.iter().map(|(name, _)| name.as_str()))
+            {
+                docker.args(["-e", name]);
+            }
+            docker.arg(&deployment.toolchain_image).arg(program);
+            docker
+        }
+    }
+}
+
+/// `uid:gid` owning the working directory, so files written in a container stay editable
+#[cfg(unix)]
+fn working_dir_owner(working_dir: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(working_dir).ok()?;
+    Some(format!("{}:{}", metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn working_dir_owner(_working_dir: &Path) -> Option<String> {
+    None
+}
+
+/// Environment for a deploy script: the operator's extra variables followed by `envs`
+///
+/// Reserved names are rejected when the config is loaded, so `envs` are never overridden.
+fn script_envs<'a>(
+    deployment: &'a DeploymentConfig,
+    envs: &[(&'a str, &'a str)],
+) -> Vec<(&'a str, &'a str)> {
+    deployment
+        .extra_env
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .chain(envs.iter().copied())
+        .collect()
+}
+
+/// Directory of the orbit SDK example used to deploy the rollup contracts
+fn rollup_dir(working_dir: &Path) -> PathBuf {
+    working_dir.join("arbitrum-orbit-sdk/examples/create-avail-rollup-eth")
+}
+
+/// Directory of the orbit setup script, which holds the docker compose file
+pub(crate) fn setup_dir(working_dir: &Path) -> PathBuf {
+    working_dir.join("orbit-setup-script")
+}
+
+/// Clone the necessary repositories, or update them if already cloned
+async fn clone_repositories(
+    deployment: &DeploymentConfig,
+    status: &mut DeploymentStatus,
+) -> Result<(), String> {
+    let working_dir = &deployment.working_dir;
+
+    // Create deployment directory, readable only by the operator since it will hold keys
+    create_private_dir(working_dir)
+        .map_err(|e| format!("Failed to create deployment directory: {}", e))?;
+    mark_working_dir(working_dir)?;
+
+    // Clone Arbitrum Orbit SDK on the Avail branch
+    let orbit_sdk_dir = working_dir.join("arbitrum-orbit-sdk");
+    retry_with_backoff(
+        NETWORK_RETRY_ATTEMPTS,
+        NETWORK_RETRY_DELAY,
+        |attempt, e| retry_clone(status, "arbitrum-orbit-sdk", attempt, e),
+        |_| {
+            sync_repository(
+                deployment,
+                ORBIT_SDK_REPO,
+                &orbit_sdk_dir,
+                Some(ORBIT_SDK_BRANCH),
+                ORBIT_SDK_SPARSE_PATHS,
+            )
+        },
+    )
+    .await
+    .map_err(|e| format!("Failed to clone arbitrum-orbit-sdk: {}", e))?;
+
+    // Clone setup script repository
+    let setup_dir = setup_dir(working_dir);
+    retry_with_backoff(
+        NETWORK_RETRY_ATTEMPTS,
+        NETWORK_RETRY_DELAY,
+        |attempt, e| retry_clone(status, "orbit-setup-script", attempt, e),
+        |_| sync_repository(deployment, SETUP_SCRIPT_REPO, &setup_dir, None, &[]),
+    )
+    .await
+    .map_err(|e| format!("Failed to clone orbit-setup-script: {}", e))?;
+
+    status.push_log("Successfully cloned required repositories".to_string());
+    Ok(())
+}
+
+/// Whether to retry a failed clone, recording the attempt if it will be retried
+///
+/// Only transient network failures are retried; a repository that doesn't exist, say,
+/// fails at once.
+fn retry_clone(status: &mut DeploymentStatus, repo: &str, attempt: u32, error: &str) -> bool {
+    if !is_transient_network_error(error) {
+        return false;
+    }
+    status.push_log(format!(
+        "Cloning {} failed (attempt {}/{}), retrying: {}",
+        repo, attempt, NETWORK_RETRY_ATTEMPTS, error
+    ));
+    true
+}
+
+/// Clone a repository into `dir`, or update it to the latest `branch` if already cloned
+///
+/// Clones are shallow unless the configured depth is 0. Shallow clones only know about
+/// the cloned branch, so updates fetch the wanted ref explicitly and check it out, rather
+/// than pulling. With sparse checkout enabled only `sparse_paths` are checked out; an
+/// empty list always checks out the whole tree.
+async fn sync_repository(
+    deployment: &DeploymentConfig,
+    repo: &str,
+    dir: &Path,
+    branch: Option<&str>,
+    sparse_paths: &[&str],
+) -> Result<(), String> {
+    let depth = deployment.git_clone_depth.to_string();
+    let shallow = deployment.git_clone_depth > 0;
+    let sparse = deployment.git_sparse_checkout && !sparse_paths.is_empty();
+
+    if dir.join(".git").exists() {
+        if sparse {
+            git(
+                deployment,
+                dir,
+                &[&["sparse-checkout", "set"][..], sparse_paths].concat(),
+            )
+            .await?;
+        }
+
+        let mut fetch = vec!["fetch", "origin", branch.unwrap_or("HEAD")];
+        if shallow {
+            fetch.extend(["--depth", &depth]);
+        }
+        git(deployment, dir, &fetch).await?;
+
+        return match branch {
+            Some(branch) => git(deployment, dir, &["checkout", "-B", branch, "FETCH_HEAD"]).await,
+            None => git(deployment, dir, &["reset", "--keep", "FETCH_HEAD"]).await,
+        };
+    }
+
+    let mut clone = vec!["clone"];
+    if shallow {
+        clone.extend(["--depth", &depth]);
+    }
+    if let Some(branch) = branch {
+        clone.extend(["--branch", branch]);
+    }
+    if sparse {
+        clone.extend(["--filter=blob:none", "--sparse"]);
+    }
+    let dir_arg = dir.display().to_string();
+    clone.extend([repo, &dir_arg]);
+    let parent = dir.parent().unwrap_or(Path::new("."));
+    git(deployment, parent, &clone).await?;
+
+    if sparse {
+        git(
+            deployment,
+            dir,
+            &[&["sparse-checkout", "set"][..], sparse_paths].concat(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Run a git command in `dir`, failing with its output if it exits unsuccessfully
+async fn git(deployment: &DeploymentConfig, dir: &Path, args: &[&str]) -> Result<(), String> {
+    let output = command("git", deployment)
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git {}: {}", args[0], e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args[0],
+            tail_bytes(&combined_output(&output), ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+    Ok(())
+}
+
+/// Pull the Docker image and clone the repositories ahead of a deployment
+///
+/// A later [`deploy_rollup`] skips these steps if the prewarm is still fresh, which keeps
+/// on-demand provisioning fast.
+pub async fn prewarm(deployment: &DeploymentConfig) -> Result<Vec<String>, String> {
+    let mut status = DeploymentStatus::default();
+    pull_docker_image(deployment, &mut status, None).await?;
+    step_timeout(
+        deployment,
+        "Cloning repositories",
+        deployment.step_timeouts.clone_secs,
+        "CLONE_TIMEOUT_SECS",
+        clone_repositories(deployment, &mut status),
+    )
+    .await??;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("System clock is before the Unix epoch: {}", e))?
+        .as_secs();
+    std::fs::write(deployment.working_dir.join(PREWARM_MARKER), now.to_string())
+        .map_err(|e| format!("Failed to record prewarm time: {}", e))?;
+
+    status.push_log("Prewarm complete".to_string());
+    Ok(status.logs)
+}
+
+/// Age of the last prewarm, if one was recorded
+pub fn prewarm_age(deployment: &DeploymentConfig) -> Option<Duration> {
+    let content = std::fs::read_to_string(deployment.working_dir.join(PREWARM_MARKER)).ok()?;
+    let prewarmed_at = UNIX_EPOCH + Duration::from_secs(content.trim().parse().ok()?);
+    SystemTime::now().duration_since(prewarmed_at).ok()
+}
+
+/// Create configuration files for deployment
+async fn create_config_files(
+    config: &AvailOrbitConfig,
+    status: &mut DeploymentStatus,
+) -> Result<(), String> {
+    let rollup_dir = rollup_dir(config.working_dir());
+
+    // Create directories if they don't exist
+    if let Err(e) = std::fs::create_dir_all(&rollup_dir) {
+        return Err(format!("Failed to create directories: {}", e));
+    }
+
+    // Generate and write .env file
+    let env_content = config.generate_env_content();
+    if let Err(e) = write_private_file(&rollup_dir.join(".env"), env_content) {
+        return Err(format!("Failed to write .env file: {}", e));
+    }
+
+    if config.is_fallback_s3_enabled() {
+        status.push_log(format!(
+            "S3 fallback enabled with object prefix {}",
+            config.fallback_s3_object_prefix()
+        ));
+    }
+
+    status.push_log("Successfully created configuration files".to_string());
+    Ok(())
+}
+
+/// Install the rollup example's dependencies, restoring them from the artifact cache if
+/// it holds an entry for the checked-out orbit SDK commit
+async fn install_dependencies(
+    config: &AvailOrbitConfig,
+    rollup_dir: &Path,
+    status: &mut DeploymentStatus,
+    progress: Option<&mpsc::Sender<DeploymentStatus>>,
+) -> Result<(), String> {
+    let deployment = config.get_deployment_config();
+    let orbit_sdk_dir = config.working_dir().join("arbitrum-orbit-sdk");
+    let cache = match &deployment.artifact_cache_dir {
+        Some(dir) => match ArtifactCache::commit_key(&orbit_sdk_dir).await {
+            Ok(key) => Some((ArtifactCache::new(dir.clone()), key)),
+            Err(e) => {
+                tracing::warn!("Artifact cache disabled: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    if let Some((cache, key)) = &cache {
+        match cache.restore(key, &orbit_sdk_dir).await {
+            Ok(true) => {
+                status.push_log(format!(
+                    "Restored dependencies from artifact cache ({})",
+                    key
+                ));
+                return Ok(());
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to restore artifact cache: {}", e),
+        }
+    }
+
+    let output = step_timeout(
+        deployment,
+        "Dependency install",
+        deployment.step_timeouts.install_secs,
+        "INSTALL_TIMEOUT_SECS",
+        output_streaming(
+            package_manager(deployment, rollup_dir, &[]).arg("install"),
+            deployment.max_captured_output_bytes,
+            live_log(status, progress, "install"),
+        ),
+    )
+    .await?
+    .map_err(|e| format!("Failed to install dependencies: {}", e))?;
+    check_exit("Dependency install", &output)?;
+
+    if let Some((cache, key)) = &cache {
+        match cache.store(key, &orbit_sdk_dir).await {
+            Ok(()) => status.push_log(format!("Stored dependencies in artifact cache ({})", key)),
+            Err(e) => tracing::warn!("Failed to store artifact cache: {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Deploy rollup contracts to the given parent chain network
+async fn deploy_contracts(
+    config: &AvailOrbitConfig,
+    network: &str,
+    status: &mut DeploymentStatus,
+    progress: Option<&mpsc::Sender<DeploymentStatus>>,
+) -> Result<(), String> {
+    let deployment = config.get_deployment_config();
+    let rollup_dir = rollup_dir(config.working_dir());
+
+    // Reuse contracts from a previous run rather than orphaning them
+    if !deployment.force_redeploy {
+        if let Some(addresses) = find_existing_deployment(config, &rollup_dir).await {
+            status.contract_addresses.extend(addresses);
+            status.push_log(
+                "Rollup contracts already deployed, reusing existing deployment".to_string(),
+            );
+            return Ok(());
+        }
+    }
+
+    install_dependencies(config, &rollup_dir, status, progress).await?;
+
+    if !deployment.extra_env.is_empty() {
+        status.push_log(format!(
+            "Passing extra environment to deploy scripts: {}",
+            deployment.extra_env_summary()
+        ));
+    }
+    let deploy_result = step_timeout(
+        deployment,
+        "Contract deployment",
+        deployment.step_timeouts.deploy_contracts_secs,
+        "DEPLOY_CONTRACTS_TIMEOUT_SECS",
+        output_streaming(
+            package_manager(deployment, &rollup_dir, &script_envs(deployment, &[])).args(
+                deployment
+                    .package_manager
+                    .run_args("deploy-avail-orbit-rollup", &["--network", network]),
+            ),
+            deployment.max_captured_output_bytes,
+            live_log(status, progress, "deploy"),
+        ),
+    )
+    .await?;
+
+    let (deploy_output, exit) = match deploy_result {
+        Ok(output) => (
+            combined_output(&output),
+            check_exit("Contract deployment", &output),
+        ),
+        Err(e) => return Err(format!("Failed to deploy rollup contracts: {}", e)),
+    };
+    record_step_output(status, DeploymentStep::DeployContracts, &deploy_output);
+    exit?;
+
+    let addresses = extract_contract_addresses(&deploy_output);
+    if addresses.is_empty() {
+        tracing::warn!(
+            "No contract addresses found in deploy output:\n{}",
+            tail_bytes(&deploy_output, ERROR_OUTPUT_TAIL_BYTES)
+        );
+    }
+    status.contract_addresses.extend(addresses);
+
+    // Verify generated files exist
+    let node_config_path = rollup_dir.join("nodeConfig.json");
+    let orbit_config_path = rollup_dir.join("orbitSetupScriptConfig.json");
+
+    if !node_config_path.exists() || !orbit_config_path.exists() {
+        return Err(format!(
+            "Deployment did not generate required configuration files. Deploy output:\n{}",
+            tail_bytes(&deploy_output, ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+
+    // The generated setup config is authoritative over addresses scraped from the output
+    status
+        .contract_addresses
+        .extend(setup_config_addresses(&rollup_dir)?);
+    let missing: Vec<&str> = REQUIRED_CONTRACTS
+        .iter()
+        .copied()
+        .filter(|name| status.contract_addresses.get(name).is_none())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Deployment did not report the addresses of: {}. Deploy output:\n{}",
+            missing.join(", "),
+            tail_bytes(&deploy_output, ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+
+    status.push_log("Successfully deployed rollup contracts".to_string());
+    Ok(())
+}
+
+/// Fail with the tail of a finished command's stderr if it exited unsuccessfully
+///
+/// Falls back to stdout for commands that report errors there.
+fn check_exit(action: &str, output: &Output) -> Result<(), String> {
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let detail = if stderr.trim().is_empty() {
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    } else {
+        stderr.into_owned()
+    };
+    Err(format!(
+        "{} failed ({}): {}",
+        action,
+        output.status,
+        tail_bytes(detail.trim(), ERROR_OUTPUT_TAIL_BYTES)
+    ))
+}
+
+/// Run a command to completion, adding its trimmed stdout to the deployment logs
+///
+/// Fails with the captured stderr if the command exits unsuccessfully.
+async fn run_logged(
+    command: &mut TokioCommand,
+    action: &str,
+    status: &mut DeploymentStatus,
+) -> Result<Output, String> {
+    let output = command
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", action, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = stdout.trim();
+    if !stdout.is_empty() {
+        status.push_log(format!(
+            "{}: {}",
+            action,
+            tail_bytes(stdout, ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+    check_exit(action, &output)?;
+    Ok(output)
+}
+
+/// Store the tail of a step's script output in the deployment status
+fn record_step_output(status: &mut DeploymentStatus, step: DeploymentStep, output: &str) {
+    status
+        .step_outputs
+        .insert(step, tail_bytes(output, MAX_STEP_OUTPUT_BYTES).to_string());
+}
+
+/// Contract addresses recorded in the deploy script's `orbitSetupScriptConfig.json`
+fn setup_config_addresses(rollup_dir: &Path) -> Result<DeployedAddresses, String> {
+    let path = rollup_dir.join("orbitSetupScriptConfig.json");
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let orbit_config: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    let mut addresses = DeployedAddresses::default();
+    for (name, value) in orbit_config
+        .as_object()
+        .ok_or_else(|| format!("{} is not a JSON object", path.display()))?
+    {
+        if let Some(address) = value.as_str().filter(|v| is_address(v)) {
+            addresses.insert(name.clone(), address);
+        }
+    }
+    Ok(addresses)
+}
+
+/// Chain info from the node config a previous run generated in the rollup directory
+fn previous_chain_info(rollup_dir: &Path) -> Result<ChainInfo, String> {
+    let path = rollup_dir.join("nodeConfig.json");
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let node_config: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    ChainInfo::from_node_config(&node_config)
+}
+
+/// Find rollup contracts deployed by a previous run
+///
+/// Returns the addresses from the previous run's `orbitSetupScriptConfig.json` if both
+/// generated config files exist, they were generated for this rollup's chain ID and the
+/// current parent chain, and the rollup contract has code on the parent chain.
+async fn find_existing_deployment(
+    config: &AvailOrbitConfig,
+    rollup_dir: &Path,
+) -> Option<DeployedAddresses> {
+    if !rollup_dir.join("nodeConfig.json").exists() {
+        return None;
+    }
+
+    let addresses = setup_config_addresses(rollup_dir).ok()?;
+    let rollup = addresses.get("rollup")?.to_string();
+    let chain_info = match previous_chain_info(rollup_dir) {
+        Ok(chain_info) => chain_info,
+        Err(e) => {
+            tracing::warn!("Previous node config is unusable, redeploying: {}", e);
+            return None;
+        }
+    };
+    let chain_id = config.get_metadata().chain_id.get();
+    if chain_info.chain_id != chain_id {
+        tracing::warn!(
+            "Previous deployment is for chain {}, but the rollup's chain ID is {}, redeploying",
+            chain_info.chain_id,
+            chain_id
+        );
+        return None;
+    }
+
+    let client = config.get_deployment_config().proxy.http_client().ok()?;
+    let parent_chain_rpcs = config.parent_chain_rpcs();
+    match with_failover(&parent_chain_rpcs, |rpc| get_chain_id(&client, rpc)).await {
+        Ok(parent_chain_id) if parent_chain_id != chain_info.parent_chain_id => {
+            tracing::warn!(
+                "Previous deployment is on parent chain {}, but the parent chain is now {}, redeploying",
+                chain_info.parent_chain_id,
+                parent_chain_id
+            );
+            return None;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!("Failed to query the parent chain ID: {}", e);
+            return None;
+        }
+    }
+    match with_failover(&parent_chain_rpcs, |rpc| has_code(&client, rpc, &rollup)).await {
+        Ok(true) => Some(addresses),
+        Ok(false) => {
+            tracing::warn!(
+                "Previous rollup contract {} has no code on the parent chain, redeploying",
+                rollup
+            );
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Failed to check previous rollup contract {}: {}", rollup, e);
+            None
+        }
+    }
+}
+
+/// Set up and start the rollup chain
+async fn setup_and_start_chain(
+    config: &AvailOrbitConfig,
+    status: &mut DeploymentStatus,
+) -> Result<(), String> {
+    let rollup_dir = rollup_dir(config.working_dir());
+    let setup_dir = setup_dir(config.working_dir());
+    let config_dir = setup_dir.join("config");
+
+    // Create config directory
+    if let Err(e) = std::fs::create_dir_all(&config_dir) {
+        return Err(format!("Failed to create config directory: {}", e));
+    }
+
+    // Copy configuration files
+    if let Err(e) = std::fs::copy(
+        rollup_dir.join("nodeConfig.json"),
+        config_dir.join("nodeConfig.json"),
+    ) {
+        return Err(format!("Failed to copy nodeConfig.json: {}", e));
+    }
+
+    if let Err(e) = std::fs::copy(
+        rollup_dir.join("orbitSetupScriptConfig.json"),
+        config_dir.join("orbitSetupScriptConfig.json"),
+    ) {
+        return Err(format!("Failed to copy orbitSetupScriptConfig.json: {}", e));
+    }
+
+    // The node config holds the batch poster and validator keys
+    restrict_file_permissions(&rollup_dir.join("nodeConfig.json"));
+    restrict_file_permissions(&config_dir.join("nodeConfig.json"));
+
+    // Fail before starting the node on a malformed or mismatched genesis
+    let chain_info = ChainInfo::from_node_config(&read_node_config(config.working_dir()).await?)?;
+    if chain_info.chain_id != config.get_metadata().chain_id.get() {
+        return Err(format!(
+            "Node config is for chain {}, but the rollup's chain ID is {}",
+            chain_info.chain_id,
+            config.get_metadata().chain_id
+        ));
+    }
+
+    // The configured parent chain RPC is authoritative over whatever the deploy script wrote.
+    // Nitro takes a single parent chain URL, so fallback RPCs only apply to our own queries.
+    // Inside the containers loopback is the container itself, so a parent chain on this host
+    // is reached through the Docker host gateway instead.
+    let container_rpc = container_rpc_url(config.get_parent_chain_rpc());
+    let parent_chain_rpc = container_rpc
+        .clone()
+        .unwrap_or_else(|| config.get_parent_chain_rpc().to_string());
+    let generated_rpc = modify_node_config(config.working_dir(), |node_config| {
+        let connection = node_config_section(node_config, &["parent-chain", "connection"])?;
+        let generated = connection
+            .get("url")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        connection.insert("url".to_string(), Value::String(parent_chain_rpc.clone()));
+        Ok(generated)
+    })
+    .await?;
+    if generated_rpc.as_deref() != Some(parent_chain_rpc.as_str()) {
+        status.push_log(format!(
+            "Set node config parent chain URL to {} (was {})",
+            redact_url(&parent_chain_rpc),
+            generated_rpc
+                .as_deref()
+                .map_or_else(|| "unset".to_string(), redact_url)
+        ));
+    }
+
+    // Publish the chain on the configured host ports, with diagnostics on their bind
+    // addresses. Inside the container the node must listen on all interfaces for Docker
+    // to forward to it, so only the container-side ports are set in the node config.
+    let deployment = config.get_deployment_config();
+    for warning in deployment.exposure_warnings() {
+        tracing::warn!("{}", warning);
+        status.push_log(format!("WARNING: {}", warning));
+    }
+    publish_ports(&setup_dir, deployment)?;
+    modify_node_config(config.working_dir(), |node_config| {
+        let defaults = PortAllocation::default();
+        for (name, port) in [
+            ("metrics-server", defaults.metrics),
+            ("pprof-cfg", defaults.pprof),
+        ] {
+            let section = node_config_section(node_config, &[name])?;
+            section.insert("addr".to_string(), Value::String("0.0.0.0".to_string()));
+            section.insert("port".to_string(), port.into());
+        }
+        Ok(())
+    })
+    .await?;
+
+    // The read replica follows the sequencer's feed and forwards transactions to it
+    let replica_compose = setup_dir.join(READ_REPLICA_COMPOSE_FILE);
+    if deployment.read_replica {
+        write_read_replica(config.working_dir(), deployment).await?;
+        status.push_log(format!(
+            "Configured read replica with RPC on port {}",
+            deployment.ports.replica_rpc
+        ));
+    } else if replica_compose.exists() {
+        std::fs::remove_file(&replica_compose)
+            .map_err(|e| format!("Failed to remove read replica compose file: {}", e))?;
+    }
+
+    // Start the chain under its own compose project so multiple rollups don't collide
+    let project_name = config.compose_project_name();
+    write_host_gateway(
+        deployment,
+        &setup_dir,
+        &project_name,
+        container_rpc.is_some(),
+    )
+    .await?;
+    install_compose_override(deployment, &setup_dir, &project_name, status).await?;
+    run_logged(
+        compose(deployment, &setup_dir, &project_name)
+            .arg("up")
+            .arg("-d"),
+        "Starting the rollup chain",
+        status,
+    )
+    .await
+    .map_err(|e| docker_error(&e))?;
+
+    // Get container IDs
+    let output = compose(deployment, &setup_dir, &project_name)
+        .args(["ps", "-q"])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to list the rollup containers: {}", e))?;
+    check_exit("Listing the rollup containers", &output)?;
+    let container_list = String::from_utf8_lossy(&output.stdout);
+    status.container_ids = normalize_container_ids(container_list.lines().map(String::from));
+
+    status.read_replica_container_id = None;
+    if deployment.read_replica {
+        let replica_result = compose(deployment, &setup_dir, &project_name)
+            .args(["ps", "-q", READ_REPLICA_SERVICE])
+            .output()
+            .await;
+
+        if let Ok(output) = replica_result {
+            let container_list = String::from_utf8_lossy(&output.stdout);
+            status.read_replica_container_id =
+                normalize_container_ids(container_list.lines().map(String::from))
+                    .into_iter()
+                    .next();
+        }
+    }
+
+    status.push_log("Successfully started the chain".to_string());
+    Ok(())
+}
+
+/// Deploy token bridge
+async fn deploy_token_bridge(
+    config: &AvailOrbitConfig,
+    status: &mut DeploymentStatus,
+    progress: Option<&mpsc::Sender<DeploymentStatus>>,
+) -> Result<(), String> {
+    let setup_dir = setup_dir(config.working_dir());
+
+    // The bridge can only be deployed once the chain is serving RPC requests
+    let deployment = config.get_deployment_config();
+    let client = deployment.proxy.http_client()?;
+    let l3_rpc_url = deployment.ports.rpc_url();
+    probe_rpc_ready(
+        &client,
+        &l3_rpc_url,
+        deployment.rpc_probe_attempts,
+        deployment.rpc_probe_interval(),
+    )
+    .await
+    .map_err(|e| format!("Cannot deploy token bridge: {}", e))?;
+
+    let bridge_result = step_timeout(
+        config.get_deployment_config(),
+        "Token bridge setup",
+        deployment.step_timeouts.token_bridge_secs,
+        "TOKEN_BRIDGE_TIMEOUT_SECS",
+        output_streaming(
+            package_manager(
+                config.get_deployment_config(),
+                &setup_dir,
+                &script_envs(
+                    config.get_deployment_config(),
+                    &[
+                        ("PRIVATE_KEY", config.get_deployer_private_key()),
+                        ("L2_RPC_URL", config.get_parent_chain_rpc()),
+                        ("L3_RPC_URL", l3_rpc_url.as_str()),
+                    ],
+                ),
+            )
+            .arg("run")
+            .arg("setup"),
+            config.get_deployment_config().max_captured_output_bytes,
+            live_log(status, progress, "bridge"),
+        ),
+    )
+    .await?;
+
+    let (bridge_output, exit) = match bridge_result {
+        Ok(output) => (
+            combined_output(&output),
+            check_exit("Token bridge setup", &output),
+        ),
+        Err(e) => return Err(format!("Failed to deploy token bridge: {}", e)),
+    };
+    record_step_output(status, DeploymentStep::DeployTokenBridge, &bridge_output);
+    // A bridge left by an earlier attempt is as good as a new one
+    if !is_bridge_already_set_up(&bridge_output) {
+        exit?;
+    }
+    status
+        .contract_addresses
+        .extend(extract_contract_addresses(&bridge_output));
+
+    status.push_log("Successfully deployed token bridge".to_string());
+    Ok(())
+}
+
+/// Update the rollup metadata
+pub async fn update_metadata(
+    context: &crate::OrbitContext,
+    metadata: &RollupMetadata,
+) -> Result<(), String> {
+    let mut status = context.status.lock().await;
+
+    if !status.deployed {
+        return Err("Cannot update metadata - rollup not deployed".to_string());
+    }
+
+    let mut metadata = metadata.clone();
+    metadata.parse_avail_app_id()?;
+    metadata.chain_id.check_collision()?;
+    metadata.validate_labels()?;
+    metadata.normalize_urls();
+    metadata.validate_urls()?;
+
+    // Only the mutable fields may change once the chain is deployed
+    match status.metadata.as_mut() {
+        Some(current) => {
+            current.check_immutable_fields(&metadata)?;
+            current.apply_mutable_fields(&metadata);
+        }
+        None => status.metadata = Some(metadata),
+    }
+
+    Ok(())
+}
+
+/// Rollup metadata fields that only matter to API consumers, not to the running node
+const NON_NODE_METADATA_FIELDS: &[&str] = &[
+    "metadata.name",
+    "metadata.explorer_url",
+    "metadata.local_rpc_endpoint",
+];
+
+/// Replace the operator config and rollup metadata with freshly loaded values
+///
+/// Both are validated together before anything is replaced, and the metadata fields fixed
+/// at deployment can't change. Running containers are left alone; the result reports what
+/// changed and whether a restart is needed to apply it.
+pub async fn reload_config(
+    context: &crate::OrbitContext,
+    operator_config: crate::OperatorConfig,
+    metadata: RollupMetadata,
+) -> Result<ConfigReload, String> {
+    let validated = AvailOrbitConfig::new(operator_config, metadata)?;
+    let operator_config = validated.operator_config().clone();
+    let metadata = validated.get_metadata().clone();
+
+    let mut config = context.operator_config.lock().await;
+    let mut status = context.status.lock().await;
+
+    // Like a metadata update, a reload can't change the fields baked into the node
+    if let Some(current) = &status.metadata {
+        current.check_immutable_fields(&metadata)?;
+    }
+
+    let mut changed = changed_fields(&*config, &operator_config);
+    changed.extend(
+        changed_fields(&status.metadata, &Some(metadata.clone()))
+            .into_iter()
+            .map(|field| match field.as_str() {
+                "" => "metadata".to_string(),
+                _ => format!("metadata.{}", field),
+            }),
+    );
+
+    // The deployment settings are read per operation; the rest is baked into the node's
+    // `.env` and config files when the chain starts
+    let restart_required = status.deployed
+        && changed.iter().any(|field| {
+            (!field.starts_with("deployment.")
+                || field.starts_with("deployment.ports.")
+                || field == "deployment.node_image")
+                && !NON_NODE_METADATA_FIELDS.contains(&field.as_str())
+        });
+
+    *config = operator_config;
+    status.metadata = Some(metadata);
+    status.push_log(format!("Reloaded configuration, changed: {:?}", changed));
+
+    Ok(ConfigReload {
+        changed,
+        restart_required,
+    })
+}
+
+/// Get the contract addresses recorded for the deployed rollup
+pub async fn contract_addresses(
+    context: &crate::OrbitContext,
+) -> Result<DeployedAddresses, String> {
+    let status = context.status.lock().await;
+
+    if !status.deployed {
+        return Err("Rollup not deployed".to_string());
+    }
+    if status.contract_addresses.is_empty() {
+        return Err("No contract addresses recorded for this deployment".to_string());
+    }
+
+    Ok(status.contract_addresses.clone())
+}
+
+/// Fetch Avail DA submission statistics from the running node
+pub async fn avail_stats(context: &crate::OrbitContext) -> Result<AvailStats, String> {
+    if !context.status.lock().await.deployed {
+        return Err("Rollup not deployed".to_string());
+    }
+
+    let deployment = context.deployment_config().await;
+    let client = deployment.proxy.http_client()?;
+    fetch_avail_stats(&client, &deployment.ports.metrics_url()).await
+}
+
+/// Measure how far batch posting to the parent chain trails the rollup
+///
+/// Compares the rollup's latest block with the batch count of the sequencer inbox on the
+/// parent chain, binary searching for the last block included in a posted batch.
+pub async fn batch_lag(context: &crate::OrbitContext) -> Result<BatchLag, String> {
+    let (sequencer_inbox, parent_chain_rpc) = sequencer_inbox(context).await?;
+
+    let deployment = context.deployment_config().await;
+    let client = deployment.proxy.http_client()?;
+    let rpc_url = deployment.ports.rpc_url();
+    let parent_chain_rpcs = deployment.parent_chain_rpcs(&parent_chain_rpc);
+
+    let batch_count = with_failover(&parent_chain_rpcs, |rpc| {
+        call_uint(&client, rpc, &sequencer_inbox, BATCH_COUNT_SELECTOR)
+    })
+    .await?;
+    let latest_block = get_block_number(&client, &rpc_url).await?;
+
+    // Blocks are batched in order, so find the last block whose batch has been posted. A
+    // failed lookup fails the whole measurement rather than skewing the search.
+    let is_batched = |block: u64| {
+        let client = &client;
+        let rpc_url = &rpc_url;
+        async move {
+            find_batch_containing_block(client, rpc_url, block)
+                .await
+                .map(|batch| batch.is_some_and(|batch| batch < batch_count))
+        }
+    };
+    let last_batched_block = if batch_count == 0 || !is_batched(0).await? {
+        None
+    } else {
+        let (mut low, mut high) = (0, latest_block);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if is_batched(mid).await? {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Some(low)
+    };
+
+    let first_unbatched = last_batched_block.map_or(0, |block| block + 1);
+    let lag_blocks = (latest_block + 1).saturating_sub(first_unbatched);
+    let lag_secs = if lag_blocks == 0 {
+        0
+    } else {
+        let oldest = get_block_timestamp(&client, &rpc_url, first_unbatched).await?;
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs().saturating_sub(oldest))
+            .unwrap_or_default()
+    };
+
+    Ok(BatchLag {
+        latest_block,
+        batch_count,
+        last_batched_block,
+        lag_blocks,
+        lag_secs,
+        threshold_secs: deployment.max_batch_lag_secs,
+        lagging: lag_secs > deployment.max_batch_lag_secs,
+    })
+}
+
+/// Sequencer inbox address and parent chain RPC of the deployed rollup
+async fn sequencer_inbox(context: &crate::OrbitContext) -> Result<(String, String), String> {
+    let status = context.status.lock().await;
+    let metadata = status
+        .metadata
+        .as_ref()
+        .filter(|_| status.deployed)
+        .ok_or_else(|| "Rollup not deployed".to_string())?;
+    let inbox = SEQUENCER_INBOX_NAMES
+        .iter()
+        .find_map(|name| status.contract_addresses.get(name))
+        .ok_or_else(|| "Sequencer inbox address not known".to_string())?;
+    Ok((inbox.to_string(), metadata.parent_chain_rpc.clone()))
+}
+
+/// Make the batch poster post pending batches now
+///
+/// Nitro has no RPC to flush the batch poster, so the sequencer container is restarted,
+/// which makes the poster post every pending batch that has reached its maximum delay.
+/// Waits for the sequencer inbox's batch count to grow and returns how many batches were
+/// posted, which is 0 if none were posted within the wait.
+pub async fn force_batch_post(context: &crate::OrbitContext) -> Result<u64, String> {
+    let (sequencer_inbox, parent_chain_rpc) = sequencer_inbox(context).await?;
+
+    let node_config = read_node_config(&context.working_dir().await).await?;
+    let enabled = node_config
+        .pointer("/node/batch-poster/enable")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if !enabled {
+        return Err("Batch posting is disabled in the node config".to_string());
+    }
+
+    let deployment = context.deployment_config().await;
+    let client = deployment.proxy.http_client()?;
+    let parent_chain_rpcs = deployment.parent_chain_rpcs(&parent_chain_rpc);
+    let batch_count = || {
+        with_failover(&parent_chain_rpcs, |rpc| {
+            call_uint(&client, rpc, &sequencer_inbox, BATCH_COUNT_SELECTOR)
+        })
+    };
+    let before = batch_count().await?;
+
+    let project_name = {
+        let status = context.status.lock().await;
+        let chain_id = status
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.chain_id)
+            .ok_or_else(|| "Rollup metadata missing".to_string())?;
+        deployment.compose_project_name(chain_id)
+    };
+    let output = compose(
+        &deployment,
+        &setup_dir(&deployment.working_dir),
+        &project_name,
+    )
+    .args(["restart", SEQUENCER_SERVICE])
+    .output()
+    .await
+    .map_err(|e| format!("Failed to restart the sequencer: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to restart the sequencer: {}",
+            tail_bytes(&combined_output(&output), ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+    context
+        .log("Restarted the sequencer to flush pending batches")
+        .await;
+
+    let deadline = Instant::now() + FORCE_BATCH_POST_WAIT;
+    let mut after = before;
+    while after == before && Instant::now() < deadline {
+        tokio::time::sleep(FORCE_BATCH_POST_POLL_INTERVAL).await;
+        after = batch_count().await.unwrap_or(before);
+    }
+
+    let flushed = after.saturating_sub(before);
+    context
+        .log(&format!("Batch post flush posted {} batches", flushed))
+        .await;
+    Ok(flushed)
+}
+
+/// Stop the rollup containers, keeping them and their data for a later restart
+///
+/// Containers get the configured timeout to stop before compose kills them. Returns the
+/// IDs of the containers that were stopped, which is empty if the rollup wasn't running.
+pub async fn stop_containers(context: &crate::OrbitContext) -> Result<Vec<String>, String> {
+    let mut status = context.status.lock().await;
+    if !status.deployed {
+        return Ok(Vec::new());
+    }
+
+    let deployment = context.deployment_config().await;
+    let chain_id = status
+        .metadata
+        .as_ref()
+        .map(|metadata| metadata.chain_id)
+        .ok_or_else(|| "Cannot stop - rollup metadata missing".to_string())?;
+    check_docker_daemon_access().await?;
 
-    // Step 2: Clone and set up repositories
-    clone_repositories(&mut status).await?;
+    let timeout = deployment.compose_down_timeout();
+    let mut stop = compose(
+        &deployment,
+        &setup_dir(&deployment.working_dir),
+        &deployment.compose_project_name(chain_id),
+    );
+    stop.args(["stop", "--timeout", &timeout.as_secs().to_string()])
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(timeout + COMPOSE_DOWN_GRACE, stop.output())
+        .await
+        .map_err(|_| {
+            format!(
+                "docker compose stop did not finish within {:?}",
+                timeout + COMPOSE_DOWN_GRACE
+            )
+        })?
+        .map_err(|e| format!("Failed to run docker compose stop: {}", e))?;
+    if !output.status.success() {
+        return Err(docker_error(tail_bytes(
+            &combined_output(&output),
+            ERROR_OUTPUT_TAIL_BYTES,
+        )));
+    }
 
-    // Step 3: Create configuration files
-    create_config_files(&config, &mut status).await?;
+    status.mark_stopped();
+    Ok(status.container_ids.clone())
+}
 
-    // Step 4: Deploy rollup contracts
-    deploy_contracts(&mut status).await?;
+/// Restart the rollup containers
+///
+/// A rollup stopped with [`stop_containers`] is brought back up. Returns the IDs of the
+/// containers that were stopped.
+pub async fn restart_containers(context: &crate::OrbitContext) -> Result<Vec<String>, String> {
+    let mut status = context.status.lock().await;
 
-    // Step 5: Set up and start the chain
-    setup_and_start_chain(&mut status).await?;
+    if !status.deployed && status.phase != DeploymentPhase::Stopped {
+        return Err("Cannot restart - rollup not deployed".to_string());
+    }
 
-    // Step 6: Deploy token bridge
-    deploy_token_bridge(&config, &mut status).await?;
+    let deployment = context.deployment_config().await;
+    let chain_id = status
+        .metadata
+        .as_ref()
+        .map(|metadata| metadata.chain_id)
+        .ok_or_else(|| "Cannot restart - rollup metadata missing".to_string())?;
+    check_docker_daemon_access().await?;
+    let project_name = deployment.compose_project_name(chain_id);
+
+    // Stop dependents before the services they depend on
+    let mut containers = container_services(&deployment, &status.container_ids).await;
+    containers.sort_by_key(|(_, service)| deployment.stop_rank(service));
+
+    for (container_id, service) in &containers {
+        let output = command("docker", &deployment)
+            .args(["stop", container_id])
+            .output()
+            .await
+            .map_err(|e| {
+                format!(
+                    "Failed to stop container {} ({}): {}",
+                    container_id, service, e
+                )
+            })?;
+        check_exit(
+            &format!("Stopping container {} ({})", container_id, service),
+            &output,
+        )?;
+    }
+
+    // Start them again in reverse, so dependencies are up before their dependents
+    let mut services: Vec<&str> = Vec::new();
+    for (_, service) in containers.iter().rev() {
+        if !service.is_empty() && !services.contains(&service.as_str()) {
+            services.push(service);
+        }
+    }
+    for service in &services {
+        let output = compose(
+            &deployment,
+            &setup_dir(&deployment.working_dir),
+            &project_name,
+        )
+        .args(["up", "-d", "--no-deps", service])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to restart service {}: {}", service, e))?;
+        check_exit(&format!("Restarting service {}", service), &output)?;
+    }
+
+    // Bring up anything not covered above, e.g. containers without a service label
+    let output = compose(
+        &deployment,
+        &setup_dir(&deployment.working_dir),
+        &project_name,
+    )
+    .arg("up")
+    .arg("-d")
+    .output()
+    .await
+    .map_err(|e| format!("Failed to restart rollup: {}", e))?;
+    check_exit("Restarting the rollup", &output)?;
 
     status.deployed = true;
-    Ok(status)
+    status.phase = DeploymentPhase::Complete;
+    Ok(status.container_ids.clone())
 }
 
-/// Pull the Avail Nitro Node Docker image
-async fn pull_docker_image(status: &mut DeploymentStatus) -> Result<(), String> {
-    let pull_result = TokioCommand::new("docker")
-        .args(["pull", DOCKER_IMAGE])
-        .output()
-        .await;
+/// Check that every container recorded in a persisted status is still running
+///
+/// Used on startup to take over a rollup deployed by a previous process instead of
+/// deploying it again.
+pub async fn reattach(
+    deployment: &DeploymentConfig,
+    status: &DeploymentStatus,
+) -> Result<(), String> {
+    if !status.deployed || status.container_ids.is_empty() {
+        return Err("No deployed containers recorded".to_string());
+    }
+    check_docker_daemon_access().await?;
 
-    if let Err(e) = pull_result {
-        return Err(format!("Failed to pull Docker image: {}", e));
+    let output = command("docker", deployment)
+        .args(["inspect", "--format", "{{.Id}} {{.State.Running}}"])
+        .args(&status.container_ids)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run docker inspect: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Recorded containers are gone: {}",
+            docker_error(String::from_utf8_lossy(&output.stderr).trim())
+        ));
     }
 
-    status
-        .logs
-        .push("Successfully pulled avail-nitro-node Docker image".to_string());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for container_id in &status.container_ids {
+        let running = stdout.lines().any(|line| {
+            line.trim().split_once(' ').is_some_and(|(id, running)| {
+                id.starts_with(container_id.as_str()) && running == "true"
+            })
+        });
+        if !running {
+            return Err(format!("Container {} is not running", container_id));
+        }
+    }
     Ok(())
 }
 
-/// Clone the necessary repositories
-async fn clone_repositories(status: &mut DeploymentStatus) -> Result<(), String> {
-    // Create deployment directory
-    if let Err(e) = std::fs::create_dir_all(DEPLOYMENT_DIR) {
-        return Err(format!("Failed to create deployment directory: {}", e));
-    }
+/// Deposit ETH from the deployer account into the rollup through its parent chain inbox
+///
+/// `amount` is a decimal ether value. Returns the hash of the deposit transaction.
+pub async fn deposit_eth(context: &crate::OrbitContext, amount: &str) -> Result<String, String> {
+    let wei = parse_ether(amount)?;
+    let (inbox, parent_chain_rpc) = {
+        let status = context.status.lock().await;
+        let metadata = status
+            .metadata
+            .as_ref()
+            .filter(|_| status.deployed)
+            .ok_or_else(|| "Cannot deposit - rollup not deployed".to_string())?;
+        let inbox = status
+            .contract_addresses
+            .get("inbox")
+            .ok_or_else(|| "Inbox address not known".to_string())?;
+        (inbox.to_string(), metadata.parent_chain_rpc.clone())
+    };
+    let deployment = context.deployment_config().await;
+    let deployer_key = context
+        .operator_config
+        .lock()
+        .await
+        .deployer_private_key
+        .clone();
 
-    // Clone Arbitrum Orbit SDK
-    let orbit_sdk_dir = format!("{}/arbitrum-orbit-sdk", DEPLOYMENT_DIR);
-    let clone_result = TokioCommand::new("git")
-        .args(["clone", ORBIT_SDK_REPO, &orbit_sdk_dir])
+    // The key and RPC URL are passed in the environment so they never appear on the
+    // command line
+    let output = command("cast", &deployment)
+        .args([
+            "send",
+            "--json",
+            "--value",
+            &wei.to_string(),
+            &inbox,
+            "depositEth()",
+        ])
+        .env("ETH_PRIVATE_KEY", &deployer_key)
+        .env("ETH_RPC_URL", &parent_chain_rpc)
         .output()
+        .await
+        .map_err(|e| format!("Failed to run cast: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Deposit transaction failed: {}",
+            tail_bytes(&combined_output(&output), ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+
+    let receipt: Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Invalid cast send output: {}", e))?;
+    let tx_hash = receipt["transactionHash"]
+        .as_str()
+        .ok_or_else(|| "Deposit receipt has no transaction hash".to_string())?
+        .to_string();
+    if receipt["status"].as_str() != Some("0x1") {
+        return Err(format!("Deposit transaction {} reverted", tx_hash));
+    }
+
+    context
+        .log(&format!(
+            "Deposited {} ETH into the rollup in transaction {}",
+            amount.trim(),
+            tx_hash
+        ))
         .await;
+    Ok(tx_hash)
+}
 
-    if let Err(e) = clone_result {
-        return Err(format!("Failed to clone arbitrum-orbit-sdk: {}", e));
+/// Restart the sequencer container in place, giving it `stop_timeout` to shut down
+///
+/// Unlike [`restart_containers`], the container is reused rather than recreated, so it
+/// keeps its volumes and configuration. Reports the container's state afterwards.
+pub async fn restart_sequencer(
+    context: &crate::OrbitContext,
+    stop_timeout: Duration,
+) -> Result<ContainerRestart, String> {
+    let container_ids = {
+        let status = context.status.lock().await;
+        if !status.deployed {
+            return Err("Cannot restart - rollup not deployed".to_string());
+        }
+        status.container_ids.clone()
+    };
+    let deployment = context.deployment_config().await;
+    check_docker_daemon_access().await?;
+
+    let container_id = container_services(&deployment, &container_ids)
+        .await
+        .into_iter()
+        .find(|(_, service)| service == SEQUENCER_SERVICE)
+        .map(|(id, _)| id)
+        .ok_or_else(|| "No sequencer container is tracked for this deployment".to_string())?;
+
+    let output = command("docker", &deployment)
+        .args(["restart", "--time", &stop_timeout.as_secs().to_string()])
+        .arg(&container_id)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run docker restart: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to restart the sequencer: {}",
+            docker_error(String::from_utf8_lossy(&output.stderr).trim())
+        ));
     }
 
-    // Checkout specific branch
-    let checkout_result = TokioCommand::new("git")
-        .current_dir(&orbit_sdk_dir)
-        .args(["checkout", ORBIT_SDK_BRANCH])
+    let output = command("docker", &deployment)
+        .args([
+            "inspect",
+            "--format",
+            "{{.State.Status}} {{if .State.Health}}{{.State.Health.Status}}{{end}}",
+        ])
+        .arg(&container_id)
         .output()
+        .await
+        .map_err(|e| format!("Failed to run docker inspect: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Restarted the sequencer but failed to inspect it: {}",
+            docker_error(String::from_utf8_lossy(&output.stderr).trim())
+        ));
+    }
+    let inspect = String::from_utf8_lossy(&output.stdout);
+    let mut fields = inspect.split_whitespace();
+    let restart = ContainerRestart {
+        container_id,
+        state: fields.next().unwrap_or("unknown").to_string(),
+        health: fields.next().map(str::to_string),
+    };
+
+    context
+        .log(&format!(
+            "Restarted sequencer container {} in place, now {}",
+            restart.container_id, restart.state
+        ))
         .await;
+    Ok(restart)
+}
+
+/// Compose service of each container, in the given order
+///
+/// Containers whose service can't be determined get an empty name.
+async fn container_services(
+    deployment: &DeploymentConfig,
+    container_ids: &[String],
+) -> Vec<(String, String)> {
+    let mut services = BTreeMap::new();
+    if !container_ids.is_empty() {
+        let inspect = command("docker", deployment)
+            .arg("inspect")
+            .args([
+                "--format",
+                "{{.Id}} {{index .Config.Labels \"com.docker.compose.service\"}}",
+            ])
+            .args(container_ids)
+            .output()
+            .await;
 
-    if let Err(e) = checkout_result {
-        return Err(format!("Failed to checkout branch: {}", e));
+        if let Ok(output) = inspect {
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                if let Some((id, service)) = line.trim().split_once(' ') {
+                    services.insert(id.to_string(), service.to_string());
+                }
+            }
+        }
     }
 
-    // Clone setup script repository
-    let setup_script_dir = format!("{}/orbit-setup-script", DEPLOYMENT_DIR);
-    let clone_setup_result = TokioCommand::new("git")
-        .args(["clone", SETUP_SCRIPT_REPO, &setup_script_dir])
+    container_ids
+        .iter()
+        .map(|id| {
+            let service = services
+                .iter()
+                .find(|(full_id, _)| full_id.starts_with(id.as_str()))
+                .map(|(_, service)| service.clone())
+                .unwrap_or_default();
+            (id.clone(), service)
+        })
+        .collect()
+}
+
+/// Update the token bridge
+///
+/// The bridge setup script is retried with exponential backoff. If its output reports
+/// that the bridge is already set up, the update is treated as successful rather than
+/// re-run. Any bridge addresses found in the output are stored in the deployment status
+/// and returned.
+pub async fn update_rollup_bridge(
+    context: &crate::OrbitContext,
+) -> Result<DeployedAddresses, String> {
+    let parent_chain_rpc = {
+        let status = context.status.lock().await;
+
+        if !status.deployed {
+            return Err("Cannot update bridge - rollup not deployed".to_string());
+        }
+
+        status
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.parent_chain_rpc.clone())
+            .ok_or_else(|| "Cannot update bridge - rollup metadata missing".to_string())?
+    };
+
+    let deployer_private_key = context
+        .operator_config
+        .lock()
+        .await
+        .deployer_private_key
+        .clone();
+    let deployment = context.deployment_config().await;
+    let setup_dir = setup_dir(&deployment.working_dir);
+
+    let output = retry_with_backoff(
+        BRIDGE_UPDATE_ATTEMPTS,
+        BRIDGE_UPDATE_BACKOFF,
+        |_, _| true,
+        |attempt| {
+            let setup_dir = setup_dir.clone();
+            let parent_chain_rpc = parent_chain_rpc.clone();
+            let deployer_private_key = deployer_private_key.clone();
+            let deployment = deployment.clone();
+            async move {
+                tracing::info!("Running token bridge setup (attempt {})", attempt);
+                let output = package_manager(
+                    &deployment,
+                    &setup_dir,
+                    &script_envs(
+                        &deployment,
+                        &[
+                            ("PRIVATE_KEY", deployer_private_key.as_str()),
+                            ("L2_RPC_URL", parent_chain_rpc.as_str()),
+                            ("L3_RPC_URL", deployment.ports.rpc_url().as_str()),
+                        ],
+                    ),
+                )
+                .arg("run")
+                .arg("setup")
+                .output()
+                .await
+                .map_err(|e| format!("Failed to execute bridge update command: {}", e))?;
+
+                let combined = format!(
+                    "{}\n{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+
+                if output.status.success() || is_bridge_already_set_up(&combined) {
+                    Ok(combined)
+                } else {
+                    Err(format!(
+                        "Failed to update token bridge: {}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ))
+                }
+            }
+        },
+    )
+    .await?;
+
+    let bridge_addresses = extract_contract_addresses(&output);
+    let mut status = context.status.lock().await;
+    if is_bridge_already_set_up(&output) {
+        status.push_log("Token bridge already set up, nothing to update".to_string());
+    } else {
+        status.push_log("Successfully updated token bridge".to_string());
+    }
+    status.contract_addresses.extend(bridge_addresses.clone());
+
+    Ok(bridge_addresses)
+}
+
+/// Whether bridge setup output indicates the bridge was already deployed
+fn is_bridge_already_set_up(output: &str) -> bool {
+    let output = output.to_lowercase();
+    BRIDGE_ALREADY_SET_UP_MARKERS
+        .iter()
+        .any(|marker| output.contains(marker))
+}
+
+/// Compose file shipped by the orbit setup script
+fn compose_file(setup_dir: &Path) -> PathBuf {
+    setup_dir.join("docker-compose.yaml")
+}
+
+/// Create a `docker compose` command for the rollup's project in `setup_dir`
+///
+/// The read replica's compose file and the operator's override are included whenever
+/// they exist, so every compose command manages the whole stack. The override comes last,
+/// so its settings win when the files are merged.
+pub(crate) fn compose(
+    deployment: &DeploymentConfig,
+    setup_dir: &Path,
+    project_name: &str,
+) -> TokioCommand {
+    let mut command = command("docker", deployment);
+    command
+        .current_dir(setup_dir)
+        .arg("compose")
+        .args(["-p", project_name]);
+
+    let extra_files: Vec<PathBuf> = [
+        READ_REPLICA_COMPOSE_FILE,
+        HOST_GATEWAY_COMPOSE_FILE,
+        COMPOSE_OVERRIDE_FILE,
+    ]
+    .iter()
+    .map(|file| setup_dir.join(file))
+    .filter(|path| path.exists())
+    .collect();
+    if !extra_files.is_empty() {
+        command.arg("-f").arg(compose_file(setup_dir));
+        for path in extra_files {
+            command.arg("-f").arg(path);
+        }
+    }
+    command
+}
+
+/// Map `host.docker.internal` to the Docker host gateway in every service, or remove the
+/// mapping if it isn't `needed`
+///
+/// Linux Docker doesn't define the name by default. The services are listed after the read
+/// replica's compose file is in place, so the replica gets the mapping too.
+async fn write_host_gateway(
+    deployment: &DeploymentConfig,
+    setup_dir: &Path,
+    project_name: &str,
+    needed: bool,
+) -> Result<(), String> {
+    let path = setup_dir.join(HOST_GATEWAY_COMPOSE_FILE);
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove host gateway compose file: {}", e))?;
+    }
+    if !needed {
+        return Ok(());
+    }
+
+    let output = compose(deployment, setup_dir, project_name)
+        .args(["config", "--services"])
         .output()
-        .await;
+        .await
+        .map_err(|e| format!("Failed to list the compose services: {}", e))?;
+    check_exit("Listing the compose services", &output)?;
+    let host = format!("{}:host-gateway", DOCKER_HOST_NAME);
+    let services: serde_json::Map<String, Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|service| !service.is_empty())
+        .map(|service| (service.to_string(), json!({ "extra_hosts": [host] })))
+        .collect();
+
+    // Compose accepts JSON, which saves a YAML dependency
+    let content = serde_json::to_string_pretty(&json!({ "services": services }))
+        .map_err(|e| format!("Failed to serialize host gateway compose file: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write host gateway compose file: {}", e))
+}
+
+/// Install the operator's compose override next to the setup script's compose file
+///
+/// The merged stack is checked with `docker compose config` first, so a malformed
+/// override fails here rather than when the chain is started. A previously installed
+/// override is removed if none is configured anymore.
+async fn install_compose_override(
+    deployment: &DeploymentConfig,
+    setup_dir: &Path,
+    project_name: &str,
+    status: &mut DeploymentStatus,
+) -> Result<(), String> {
+    let installed = setup_dir.join(COMPOSE_OVERRIDE_FILE);
+    let Some(source) = &deployment.compose_override_file else {
+        if installed.exists() {
+            std::fs::remove_file(&installed)
+                .map_err(|e| format!("Failed to remove compose override: {}", e))?;
+        }
+        return Ok(());
+    };
 
-    if let Err(e) = clone_setup_result {
-        return Err(format!("Failed to clone orbit-setup-script: {}", e));
+    std::fs::copy(source, &installed).map_err(|e| {
+        format!(
+            "Failed to copy compose override {}: {}",
+            source.display(),
+            e
+        )
+    })?;
+    let output = compose(deployment, setup_dir, project_name)
+        .args(["config", "--quiet"])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run docker compose config: {}", e))?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&installed);
+        return Err(format!(
+            "Invalid compose override {}: {}",
+            source.display(),
+            tail_bytes(&combined_output(&output), ERROR_OUTPUT_TAIL_BYTES)
+        ));
     }
 
-    status
-        .logs
-        .push("Successfully cloned required repositories".to_string());
+    status.push_log(format!(
+        "Merging compose override {} into the stack",
+        source.display()
+    ));
     Ok(())
 }
 
-/// Create configuration files for deployment
-async fn create_config_files(
-    config: &AvailOrbitConfig,
-    status: &mut DeploymentStatus,
+/// Write the read replica's node config and compose file
+///
+/// The replica's node config is derived from the sequencer's: sequencing, batch posting
+/// and staking are disabled along with their keys, blocks come from the sequencer's feed,
+/// and transactions are forwarded to the sequencer. The replica keeps its own chain data
+/// in a named volume and publishes its RPC on the allocated replica port.
+async fn write_read_replica(
+    working_dir: &Path,
+    deployment: &DeploymentConfig,
 ) -> Result<(), String> {
-    let rollup_dir = format!(
-        "{}/arbitrum-orbit-sdk/examples/create-avail-rollup-eth",
-        DEPLOYMENT_DIR
+    let setup_dir = setup_dir(working_dir);
+    let defaults = PortAllocation::default();
+
+    let mut node_config = read_node_config(working_dir).await?;
+    let node = node_config_section(&mut node_config, &["node"])?;
+    node.insert("sequencer".to_string(), Value::Bool(false));
+    node.insert(
+        "feed".to_string(),
+        json!({
+            "input": { "url": [format!("ws://{}:{}", SEQUENCER_SERVICE, defaults.feed)] }
+        }),
+    );
+    for role in ["batch-poster", "staker", "delayed-sequencer"] {
+        node.insert(role.to_string(), json!({ "enable": false }));
+    }
+    node_config_section(&mut node_config, &["execution", "sequencer"])?
+        .insert("enable".to_string(), Value::Bool(false));
+    node_config_section(&mut node_config, &["execution"])?.insert(
+        "forwarding-target".to_string(),
+        Value::String(format!("http://{}:{}", SEQUENCER_SERVICE, defaults.rpc)),
     );
+    let http = node_config_section(&mut node_config, &["http"])?;
+    http.insert("addr".to_string(), Value::String("0.0.0.0".to_string()));
+    http.insert("port".to_string(), defaults.rpc.into());
 
-    // Create directories if they don't exist
-    if let Err(e) = std::fs::create_dir_all(&rollup_dir) {
-        return Err(format!("Failed to create directories: {}", e));
+    let content = serde_json::to_string_pretty(&node_config)
+        .map_err(|e| format!("Failed to serialize read replica node config: {}", e))?;
+    write_private_file(&setup_dir.join(READ_REPLICA_NODE_CONFIG), content)
+        .map_err(|e| format!("Failed to write read replica node config: {}", e))?;
+
+    // Compose accepts JSON, which saves a YAML dependency
+    let replica_compose = json!({
+        "services": {
+            READ_REPLICA_SERVICE: {
+                "image": deployment.node_image,
+                "restart": "unless-stopped",
+                "depends_on": [SEQUENCER_SERVICE],
+                "ports": [format!("{}:{}", deployment.ports.replica_rpc, defaults.rpc)],
+                "volumes": [
+                    format!("./{}:/config/nodeConfig.json:ro", READ_REPLICA_NODE_CONFIG),
+                    "replica-data:/home/user/.arbitrum",
+                ],
+                "command": ["--conf.file", "/config/nodeConfig.json"],
+            }
+        },
+        "volumes": { "replica-data": {} }
+    });
+    let content = serde_json::to_string_pretty(&replica_compose)
+        .map_err(|e| format!("Failed to serialize read replica compose file: {}", e))?;
+    std::fs::write(setup_dir.join(READ_REPLICA_COMPOSE_FILE), content)
+        .map_err(|e| format!("Failed to write read replica compose file: {}", e))
+}
+
+/// Rewrite the host side of the compose file's port mappings to the allocated ports, and
+/// its Nitro node image to the configured one
+///
+/// Metrics and pprof are also bound to their configured host addresses. The upstream file
+/// is kept as `docker-compose.yaml.orig` and the mappings are always rewritten from it, so
+/// changing the allocation or image between deployments is safe.
+fn publish_ports(setup_dir: &Path, deployment: &DeploymentConfig) -> Result<(), String> {
+    let compose_path = compose_file(setup_dir);
+    let original_path = compose_path.with_extension("yaml.orig");
+    let source_path = if original_path.exists() {
+        &original_path
+    } else {
+        &compose_path
+    };
+    let original = std::fs::read_to_string(source_path)
+        .map_err(|e| format!("Failed to read compose file: {}", e))?;
+
+    let compose = rewrite_compose(&original, deployment);
+    if compose == original {
+        return Ok(());
+    }
+    if !original_path.exists() {
+        std::fs::copy(&compose_path, &original_path)
+            .map_err(|e| format!("Failed to back up compose file: {}", e))?;
     }
+    std::fs::write(&compose_path, compose)
+        .map_err(|e| format!("Failed to write compose file: {}", e))
+}
 
-    // Generate and write .env file
-    let env_content = config.generate_env_content();
-    if let Err(e) = std::fs::write(format!("{}/{}", &rollup_dir, ".env"), env_content) {
-        return Err(format!("Failed to write .env file: {}", e));
+/// Apply the port allocation and node image to the upstream compose file's content
+fn rewrite_compose(original: &str, deployment: &DeploymentConfig) -> String {
+    let defaults = PortAllocation::default().all();
+    let host_ports: BTreeMap<u16, String> = defaults
+        .iter()
+        .zip(deployment.ports.all())
+        .map(|((name, default), (_, port))| {
+            let host = match *name {
+                "metrics" => host_binding(deployment.metrics_bind_addr),
+                "pprof" => host_binding(deployment.pprof_bind_addr),
+                _ => String::new(),
+            };
+            (*default, format!("{}{}", host, port))
+        })
+        .collect();
+
+    let mut compose = remap_host_ports(original, &host_ports)
+        .lines()
+        .map(|line| match line.split_once("image:") {
+            Some((indent, image))
+                if image.trim().trim_matches('"').starts_with(NODE_IMAGE_REPO) =>
+            {
+                format!("{}image: {}", indent, deployment.node_image)
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if original.ends_with('\n') {
+        compose.push('\n');
     }
+    compose
+}
 
-    status
-        .logs
-        .push("Successfully created configuration files".to_string());
-    Ok(())
+/// Replace the host side of port mappings such as `- "8449:8449"` or `- 8449:8449`
+///
+/// Each mapping is rewritten at most once, in a single pass, so a port shifted onto
+/// another default port isn't shifted again.
+fn remap_host_ports(compose: &str, host_ports: &BTreeMap<u16, String>) -> String {
+    let mut remapped = String::with_capacity(compose.len());
+    let mut rest = compose;
+    while let Some(c) = rest.chars().next() {
+        let prefix = match c {
+            '"' => Some("\""),
+            '-' if rest.starts_with("- ") => Some("- "),
+            _ => None,
+        };
+        if let Some(prefix) = prefix {
+            let after = &rest[prefix.len()..];
+            let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let mapped = after[digits..]
+                .starts_with(':')
+                .then(|| after[..digits].parse::<u16>().ok())
+                .flatten()
+                .and_then(|port| host_ports.get(&port));
+            if let Some(host_port) = mapped {
+                remapped.push_str(prefix);
+                remapped.push_str(host_port);
+                rest = &after[digits..];
+                continue;
+            }
+        }
+        remapped.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    remapped
 }
 
-/// Deploy rollup contracts
-async fn deploy_contracts(status: &mut DeploymentStatus) -> Result<(), String> {
-    let rollup_dir = format!(
-        "{}/arbitrum-orbit-sdk/examples/create-avail-rollup-eth",
-        DEPLOYMENT_DIR
-    );
+/// `url` with a loopback host replaced by the name containers reach this host under
+///
+/// Returns `None` if `url` doesn't point at this host.
+fn container_rpc_url(url: &str) -> Option<String> {
+    let mut url = reqwest::Url::parse(url).ok()?;
+    match url.host_str()? {
+        "localhost" | "127.0.0.1" | "[::1]" => {}
+        _ => return None,
+    }
+    url.set_host(Some(DOCKER_HOST_NAME)).ok()?;
+    Some(url.to_string())
+}
 
-    // Install dependencies
-    let install_result = TokioCommand::new("yarn")
-        .current_dir(&rollup_dir)
-        .arg("install")
-        .output()
-        .await;
+/// Host address prefix of a compose port mapping, empty for all interfaces
+fn host_binding(addr: IpAddr) -> String {
+    match addr {
+        addr if addr.is_unspecified() => String::new(),
+        IpAddr::V4(addr) => format!("{}:", addr),
+        IpAddr::V6(addr) => format!("[{}]:", addr),
+    }
+}
+
+/// Path of the node config used by the running chain
+fn node_config_path(working_dir: &Path) -> PathBuf {
+    setup_dir(working_dir).join("config/nodeConfig.json")
+}
+
+/// Read the running chain's node config
+async fn read_node_config(working_dir: &Path) -> Result<Value, String> {
+    let content = tokio::fs::read_to_string(node_config_path(working_dir))
+        .await
+        .map_err(|e| format!("Failed to read node config: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse node config: {}", e))
+}
 
-    if let Err(e) = install_result {
-        return Err(format!("Failed to install dependencies: {}", e));
+/// Object at `path` in a node config, creating missing or null sections along the way
+///
+/// Fails instead of panicking if the config or a section on the path isn't an object.
+fn node_config_section<'a>(
+    node_config: &'a mut Value,
+    path: &[&str],
+) -> Result<&'a mut serde_json::Map<String, Value>, String> {
+    let mut section = node_config
+        .as_object_mut()
+        .ok_or("Node config is not a JSON object")?;
+    for (depth, key) in path.iter().enumerate() {
+        let value = section.entry(*key).or_insert(Value::Null);
+        if value.is_null() {
+            *value = json!({});
+        }
+        section = value.as_object_mut().ok_or_else(|| {
+            format!(
+                "Node config section {} is not a JSON object",
+                path[..=depth].join(".")
+            )
+        })?;
     }
+    Ok(section)
+}
 
-    let deploy_result = TokioCommand::new("yarn")
-        .current_dir(&rollup_dir)
-        .arg("run")
-        .arg("deploy-avail-orbit-rollup")
-        .output()
-        .await;
+/// Read the running chain's node config, apply `update` to it, and write it back
+async fn modify_node_config<T>(
+    working_dir: &Path,
+    update: impl FnOnce(&mut Value) -> Result<T, String>,
+) -> Result<T, String> {
+    let path = node_config_path(working_dir);
+    let mut node_config = read_node_config(working_dir).await?;
+
+    let result = update(&mut node_config)?;
+
+    let content = serde_json::to_string_pretty(&node_config)
+        .map_err(|e| format!("Failed to serialize node config: {}", e))?;
+    write_private_file(&path, content)
+        .map_err(|e| format!("Failed to write node config: {}", e))?;
 
-    if let Err(e) = deploy_result {
-        return Err(format!("Failed to deploy rollup contracts: {}", e));
+    Ok(result)
+}
+
+/// Get the running chain's node config with all secrets redacted
+pub async fn export_node_config(context: &crate::OrbitContext) -> Result<Value, String> {
+    if !context.status.lock().await.deployed {
+        return Err("Cannot export node config - rollup not deployed".to_string());
     }
 
-    // Verify generated files exist
-    let node_config_path = Path::new(&rollup_dir).join("nodeConfig.json");
-    let orbit_config_path = Path::new(&rollup_dir).join("orbitSetupScriptConfig.json");
+    let mut node_config = read_node_config(&context.working_dir().await).await?;
+    redact_secret_values(&mut node_config);
+    Ok(node_config)
+}
 
-    if !node_config_path.exists() || !orbit_config_path.exists() {
-        return Err("Deployment did not generate required configuration files".to_string());
+/// Get the running chain's parsed and validated chain info
+pub async fn chain_info(context: &crate::OrbitContext) -> Result<ChainInfo, String> {
+    if !context.status.lock().await.deployed {
+        return Err("Rollup not deployed".to_string());
     }
 
-    status
-        .logs
-        .push("Successfully deployed rollup contracts".to_string());
-    Ok(())
+    let node_config = read_node_config(&context.working_dir().await).await?;
+    ChainInfo::from_node_config(&node_config)
 }
 
-/// Set up and start the rollup chain
-async fn setup_and_start_chain(status: &mut DeploymentStatus) -> Result<(), String> {
-    let rollup_dir = format!(
-        "{}/arbitrum-orbit-sdk/examples/create-avail-rollup-eth",
-        DEPLOYMENT_DIR
-    );
-    let setup_dir = format!("{}/orbit-setup-script", DEPLOYMENT_DIR);
-    let config_dir = format!("{}/config", setup_dir);
+/// Store a redacted node config export in the working directory, returning its path
+///
+/// Used when the export is too large to return as a job result.
+pub async fn store_node_config_export(
+    context: &crate::OrbitContext,
+    contents: &str,
+) -> Result<PathBuf, String> {
+    let exports_dir = context.working_dir().await.join("exports");
+    create_private_dir(&exports_dir)
+        .map_err(|e| format!("Failed to create exports directory: {}", e))?;
 
-    // Create config directory
-    if let Err(e) = std::fs::create_dir_all(&config_dir) {
-        return Err(format!("Failed to create config directory: {}", e));
-    }
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("System clock is before the Unix epoch: {}", e))?
+        .as_secs();
+    let path = exports_dir.join(format!("node-config-{}.json", timestamp));
+    write_private_file(&path, contents)
+        .map_err(|e| format!("Failed to store node config export: {}", e))?;
+    Ok(path)
+}
 
-    // Copy configuration files
-    if let Err(e) = std::fs::copy(
-        format!("{}/nodeConfig.json", rollup_dir),
-        format!("{}/nodeConfig.json", config_dir),
-    ) {
-        return Err(format!("Failed to copy nodeConfig.json: {}", e));
+/// Set the batch poster's `max-items` and restart the chain to apply it
+///
+/// Returns the previous value, if the node config had one.
+pub async fn set_batch_max_items(
+    context: &crate::OrbitContext,
+    max_items: u64,
+) -> Result<Option<u64>, String> {
+    if max_items == 0 || max_items > MAX_BATCH_ITEMS {
+        return Err(format!(
+            "Batch max items must be between 1 and {}, got {}",
+            MAX_BATCH_ITEMS, max_items
+        ));
     }
 
-    if let Err(e) = std::fs::copy(
-        format!("{}/orbitSetupScriptConfig.json", rollup_dir),
-        format!("{}/orbitSetupScriptConfig.json", config_dir),
-    ) {
-        return Err(format!("Failed to copy orbitSetupScriptConfig.json: {}", e));
+    if !context.status.lock().await.deployed {
+        return Err("Cannot update batch poster - rollup not deployed".to_string());
     }
 
-    // Start the chain
-    let start_result = TokioCommand::new("docker")
-        .current_dir(&setup_dir)
-        .arg("compose")
-        .arg("up")
-        .arg("-d")
-        .output()
+    let previous = modify_node_config(&context.working_dir().await, |node_config| {
+        let batch_poster = node_config
+            .pointer_mut("/node/batch-poster")
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| "Node config has no node.batch-poster section".to_string())?;
+        let previous = batch_poster.get("max-items").and_then(Value::as_u64);
+        batch_poster.insert("max-items".to_string(), max_items.into());
+        Ok(previous)
+    })
+    .await?;
+
+    restart_containers(context).await?;
+
+    context
+        .log(&format!("Batch poster max-items set to {}", max_items))
         .await;
+    Ok(previous)
+}
 
-    if let Err(e) = start_result {
-        return Err(format!("Failed to start the rollup chain: {}", e));
+/// Set the chain's minimum L2 base fee through the ArbOwner precompile
+///
+/// The transaction is sent with the deployer key, which owns the chain, and the new value
+/// is read back from ArbGasInfo to confirm it was applied.
+pub async fn set_min_base_fee(context: &crate::OrbitContext, fee_wei: u64) -> Result<(), String> {
+    if fee_wei == 0 || fee_wei > MAX_MIN_L2_BASE_FEE {
+        return Err(format!(
+            "Minimum L2 base fee must be between 1 and {} wei, got {}",
+            MAX_MIN_L2_BASE_FEE, fee_wei
+        ));
     }
 
-    // Get container IDs
-    let containers_result = TokioCommand::new("docker")
-        .current_dir(&setup_dir)
-        .arg("compose")
-        .args(["ps", "-q"])
-        .output()
-        .await;
+    if !context.status.lock().await.deployed {
+        return Err("Cannot set base fee - rollup not deployed".to_string());
+    }
+
+    let deployment = context.deployment_config().await;
+    let owner_key = context
+        .operator_config
+        .lock()
+        .await
+        .deployer_private_key
+        .clone();
 
-    if let Ok(output) = containers_result {
-        let container_list = String::from_utf8_lossy(&output.stdout);
-        status.container_ids = container_list.lines().map(|s| s.to_string()).collect();
+    send_owner_transaction(
+        &deployment,
+        &owner_key,
+        "setMinimumL2BaseFee(uint256)",
+        fee_wei,
+    )
+    .await?;
+
+    let applied = call_gas_info(&deployment, "getMinimumGasPrice()(uint256)")
+        .await?
+        .first()
+        .copied()
+        .ok_or_else(|| "Failed to read back minimum L2 base fee".to_string())?;
+    if applied != fee_wei {
+        return Err(format!(
+            "Minimum L2 base fee reads back as {} wei, expected {}",
+            applied, fee_wei
+        ));
     }
 
-    status
-        .logs
-        .push("Successfully started the chain".to_string());
+    context.status.lock().await.min_l2_base_fee = Some(fee_wei);
+    context
+        .log(&format!("Minimum L2 base fee set to {} wei", fee_wei))
+        .await;
     Ok(())
 }
 
-/// Deploy token bridge
-async fn deploy_token_bridge(
-    config: &AvailOrbitConfig,
-    status: &mut DeploymentStatus,
-) -> Result<(), String> {
-    let setup_dir = format!("{}/orbit-setup-script", DEPLOYMENT_DIR);
-
-    let bridge_result = TokioCommand::new("yarn")
-        .current_dir(&setup_dir)
-        .env("PRIVATE_KEY", config.get_deployer_private_key())
-        .env("L2_RPC_URL", "https://sepolia-rollup.arbitrum.io/rpc")
-        .env("L3_RPC_URL", "http://localhost:8449")
-        .arg("run")
-        .arg("setup")
-        .output()
+/// Set the L2 speed limit, the gas per second the chain targets before raising prices
+///
+/// Submits an ArbOwner `setSpeedLimit` transaction with the operator's owner key and
+/// confirms the new limit through ArbGasInfo.
+pub async fn set_speed_limit(context: &crate::OrbitContext, limit: u64) -> Result<(), String> {
+    if !(MIN_SPEED_LIMIT..=MAX_SPEED_LIMIT).contains(&limit) {
+        return Err(format!(
+            "Speed limit must be between {} and {} gas per second, got {}",
+            MIN_SPEED_LIMIT, MAX_SPEED_LIMIT, limit
+        ));
+    }
+
+    set_gas_accounting_param(context, "setSpeedLimit(uint64)", 0, limit).await?;
+
+    context.status.lock().await.speed_limit = Some(limit);
+    context
+        .log(&format!("Speed limit set to {} gas per second", limit))
         .await;
+    Ok(())
+}
 
-    if let Err(e) = bridge_result {
-        return Err(format!("Failed to deploy token bridge: {}", e));
+/// Set the L2 block gas limit
+///
+/// Submits an ArbOwner `setMaxTxGasLimit` transaction, which bounds the gas of each
+/// block, with the operator's owner key and confirms the new limit through ArbGasInfo.
+pub async fn set_block_gas_limit(context: &crate::OrbitContext, limit: u64) -> Result<(), String> {
+    if !(MIN_BLOCK_GAS_LIMIT..=MAX_BLOCK_GAS_LIMIT).contains(&limit) {
+        return Err(format!(
+            "Block gas limit must be between {} and {}, got {}",
+            MIN_BLOCK_GAS_LIMIT, MAX_BLOCK_GAS_LIMIT, limit
+        ));
     }
 
-    status
-        .logs
-        .push("Successfully deployed token bridge".to_string());
+    set_gas_accounting_param(context, "setMaxTxGasLimit(uint64)", 2, limit).await?;
+
+    context.status.lock().await.block_gas_limit = Some(limit);
+    context
+        .log(&format!("Block gas limit set to {}", limit))
+        .await;
     Ok(())
 }
 
-/// Update the rollup metadata
-pub async fn update_metadata(
+/// Set a gas accounting parameter through ArbOwner and confirm it reads back
+///
+/// `index` is the parameter's position in ArbGasInfo's `getGasAccountingParams` result:
+/// speed limit, gas pool max, block gas limit.
+async fn set_gas_accounting_param(
     context: &crate::OrbitContext,
-    metadata: &RollupMetadata,
+    signature: &str,
+    index: usize,
+    value: u64,
 ) -> Result<(), String> {
-    let mut status = context.status.lock().await;
-
-    if !status.deployed {
-        return Err("Cannot update metadata - rollup not deployed".to_string());
+    if !context.status.lock().await.deployed {
+        return Err("Cannot tune gas accounting - rollup not deployed".to_string());
     }
 
-    // Update the metadata
-    status.metadata = Some(metadata.clone());
+    let deployment = context.deployment_config().await;
+    let owner_key = context
+        .operator_config
+        .lock()
+        .await
+        .deployer_private_key
+        .clone();
+
+    send_owner_transaction(&deployment, &owner_key, signature, value).await?;
 
+    let params = call_gas_info(
+        &deployment,
+        "getGasAccountingParams()(uint256,uint256,uint256)",
+    )
+    .await?;
+    let applied = params
+        .get(index)
+        .copied()
+        .ok_or_else(|| format!("Failed to read back gas accounting params: {:?}", params))?;
+    if applied != value {
+        return Err(format!(
+            "{} reads back as {}, expected {}",
+            signature, applied, value
+        ));
+    }
     Ok(())
 }
 
-/// Restart the rollup containers
-pub async fn restart_containers(context: &crate::OrbitContext) -> Result<(), String> {
-    let status = context.status.lock().await;
-
-    if !status.deployed {
-        return Err("Cannot restart - rollup not deployed".to_string());
+/// Submit an ArbOwner transaction calling `signature` with a single integer argument
+async fn send_owner_transaction(
+    deployment: &DeploymentConfig,
+    owner_key: &str,
+    signature: &str,
+    value: u64,
+) -> Result<(), String> {
+    // The key and RPC URL are passed in the environment so they never appear on the
+    // command line
+    let output = command("cast", deployment)
+        .args(["send", ARB_OWNER_ADDRESS, signature, &value.to_string()])
+        .env("ETH_PRIVATE_KEY", owner_key)
+        .env("ETH_RPC_URL", deployment.ports.rpc_url())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run cast: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} transaction failed: {}",
+            signature,
+            tail_bytes(&combined_output(&output), ERROR_OUTPUT_TAIL_BYTES)
+        ));
     }
+    Ok(())
+}
 
-    // Stop containers
-    for container_id in &status.container_ids {
-        let stop_result = std::process::Command::new("docker")
-            .args(["stop", container_id])
-            .output();
+/// Call an ArbGasInfo getter and parse its integer return values, one per output line
+async fn call_gas_info(deployment: &DeploymentConfig, signature: &str) -> Result<Vec<u64>, String> {
+    let output = command("cast", deployment)
+        .args(["call", ARB_GAS_INFO_ADDRESS, signature])
+        .env("ETH_RPC_URL", deployment.ports.rpc_url())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run cast: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // cast may append a scientific-notation hint, e.g. `100000000 [1e8]`
+    stdout
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .next()
+                .and_then(|value| value.parse::<u64>().ok())
+                .ok_or_else(|| format!("Unexpected {} output: {}", signature, stdout.trim()))
+        })
+        .collect()
+}
 
-        if let Err(e) = stop_result {
-            return Err(format!("Failed to stop container {}: {}", container_id, e));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OperatorConfig;
+
+    fn local_fork_config(working_dir: PathBuf) -> OperatorConfig {
+        let mut deployment = DeploymentConfig {
+            settlement_layer: SettlementLayer::LocalFork,
+            status_path: Some(working_dir.join("status.json")),
+            working_dir,
+            ..Default::default()
+        };
+        deployment.launch_anvil = false;
+        OperatorConfig {
+            deployer_private_key: String::new(),
+            batch_poster_private_key: String::new(),
+            validator_private_key: String::new(),
+            avail_addr_seed:
+                "bottom drive obey lake curtain smoke basket hold race lonely fit walk".to_string(),
+            fallback_s3_access_key: None,
+            fallback_s3_secret_key: None,
+            fallback_s3_region: None,
+            fallback_s3_object_prefix: None,
+            fallback_s3_bucket: None,
+            deployment,
         }
     }
 
-    // Start containers again
-    let setup_dir = format!("{}/orbit-setup-script", DEPLOYMENT_DIR);
-    let start_result = std::process::Command::new("docker")
-        .current_dir(setup_dir)
-        .arg("compose")
-        .arg("up")
-        .arg("-d")
-        .output();
+    fn metadata(chain_id: u64) -> RollupMetadata {
+        RollupMetadata {
+            name: "test".to_string(),
+            chain_id: chain_id.try_into().unwrap(),
+            avail_app_id: "1".to_string(),
+            parent_chain_rpc: "http://localhost:8545".to_string(),
+            local_rpc_endpoint: "http://localhost:8449".to_string(),
+            explorer_url: "http://localhost:4000".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn port_offset_maps_each_port_once() {
+        let deployment = DeploymentConfig {
+            ports: PortAllocation::default().with_offset(1).unwrap(),
+            ..Default::default()
+        };
+        let compose = "services:\n  nitro:\n    image: availj/avail-nitro-node:old\n    ports:\n      - \"8449:8449\"\n      - 8548:8548\n      - \"6070:6070\"\n      - \"6071:6071\"\n";
 
-    if let Err(e) = start_result {
-        return Err(format!("Failed to restart rollup: {}", e));
+        let rewritten = rewrite_compose(compose, &deployment);
+
+        assert!(rewritten.contains("- \"8450:8449\""), "{}", rewritten);
+        assert!(rewritten.contains("- 8549:8548"), "{}", rewritten);
+        assert!(
+            rewritten.contains("- \"127.0.0.1:6071:6070\""),
+            "{}",
+            rewritten
+        );
+        assert!(
+            rewritten.contains("- \"127.0.0.1:6072:6071\""),
+            "{}",
+            rewritten
+        );
+        assert!(rewritten.contains(&format!("image: {}", DOCKER_IMAGE)));
     }
 
-    Ok(())
-}
+    #[test]
+    fn node_config_section_creates_missing_sections() {
+        let mut node_config = json!({ "parent-chain": { "id": 1 }, "http": null });
+        node_config_section(&mut node_config, &["parent-chain", "connection"])
+            .unwrap()
+            .insert("url".to_string(), json!("http://localhost:8545"));
+        node_config_section(&mut node_config, &["http"]).unwrap();
 
-/// Update the token bridge
-pub async fn update_rollup_bridge(context: &crate::OrbitContext) -> Result<(), String> {
-    let status = context.status.lock().await;
+        assert_eq!(node_config["parent-chain"]["id"], 1);
+        assert_eq!(
+            node_config["parent-chain"]["connection"]["url"],
+            "http://localhost:8545"
+        );
+        assert_eq!(node_config["http"], json!({}));
+    }
 
-    if !status.deployed {
-        return Err("Cannot update bridge - rollup not deployed".to_string());
+    #[test]
+    fn node_config_section_rejects_non_objects() {
+        let error = node_config_section(&mut json!([]), &["http"]).unwrap_err();
+        assert_eq!(error, "Node config is not a JSON object");
+
+        let mut node_config = json!({ "parent-chain": { "connection": "http://localhost:8545" } });
+        let error =
+            node_config_section(&mut node_config, &["parent-chain", "connection"]).unwrap_err();
+        assert!(error.contains("parent-chain.connection"), "{}", error);
     }
 
-    let operator_config = context.operator_config.lock().await;
-    let setup_dir = format!("{}/orbit-setup-script", DEPLOYMENT_DIR);
+    #[tokio::test]
+    async fn reload_rejects_chain_id_change() {
+        let working_dir =
+            std::env::temp_dir().join(format!("avail-orbit-raas-reload-{}", std::process::id()));
+        let context = crate::OrbitContext::new(local_fork_config(working_dir.clone())).unwrap();
+        context.status.lock().await.metadata = Some(metadata(412346));
 
-    let result = TokioCommand::new("yarn")
-        .current_dir(setup_dir)
-        .env("PRIVATE_KEY", &operator_config.deployer_private_key)
-        .env("L2_RPC_URL", "https://sepolia-rollup.arbitrum.io/rpc")
-        .env("L3_RPC_URL", "http://localhost:8449")
-        .arg("run")
-        .arg("setup")
-        .output()
+        let result = reload_config(
+            &context,
+            local_fork_config(working_dir.clone()),
+            metadata(412347),
+        )
         .await;
+        let _ = std::fs::remove_dir_all(&working_dir);
 
-    match result {
-        Ok(output) => {
-            if output.status.success() {
-                Ok(())
-            } else {
-                Err(format!(
-                    "Failed to update token bridge: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                ))
-            }
-        }
-        Err(e) => Err(format!("Failed to execute bridge update command: {}", e)),
+        let error = result.unwrap_err();
+        assert!(error.contains("chain_id"), "{}", error);
+        let status = context.status.lock().await;
+        assert_eq!(status.metadata.as_ref().unwrap().chain_id.get(), 412346);
+    }
+
+    #[tokio::test]
+    async fn reload_applies_mutable_metadata() {
+        let working_dir = std::env::temp_dir().join(format!(
+            "avail-orbit-raas-reload-mutable-{}",
+            std::process::id()
+        ));
+        let context = crate::OrbitContext::new(local_fork_config(working_dir.clone())).unwrap();
+        context.status.lock().await.metadata = Some(metadata(412346));
+
+        let mut renamed = metadata(412346);
+        renamed.name = "renamed".to_string();
+        let result = reload_config(&context, local_fork_config(working_dir.clone()), renamed).await;
+        let _ = std::fs::remove_dir_all(&working_dir);
+
+        assert!(
+            result
+                .unwrap()
+                .changed
+                .contains(&"metadata.name".to_string())
+        );
+        let status = context.status.lock().await;
+        assert_eq!(status.metadata.as_ref().unwrap().name, "renamed");
     }
 }
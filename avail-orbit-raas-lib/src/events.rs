@@ -0,0 +1,404 @@
+//! Deployment event log for Avail Orbit RaaS
+//!
+//! Records step transitions, deployment outcomes, and job invocations as events with
+//! gap-free sequence numbers, so external orchestrators can replay them idempotently.
+//! Each event is appended to a JSON lines file before it is broadcast, so a subscriber
+//! that missed live events can always catch up from the history.
+
+use crate::types::DeploymentStep;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+/// Live events buffered per subscriber before it lags
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// What happened in a [`DeploymentEvent`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    /// A deployment started
+    DeploymentStarted,
+    /// A deployment step completed
+    StepCompleted { step: DeploymentStep },
+    /// The deployment finished and the rollup is running
+    DeploymentSucceeded,
+    /// The deployment failed
+    DeploymentFailed { error: String },
+    /// A deployment was found interrupted on startup
+    DeploymentInterrupted { step: Option<DeploymentStep> },
+    /// A state-changing job finished
+    JobCompleted {
+        job: String,
+        success: bool,
+        message: String,
+    },
+}
+
+/// An entry in the deployment event log
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentEvent {
+    /// Sequence number, starting at 1 and increasing by 1 with every event
+    pub seq: u64,
+    /// When the event was recorded, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// What happened
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// Append-only, persisted log of deployment events
+pub struct EventLog {
+    events: Arc<Mutex<Vec<DeploymentEvent>>>,
+    sender: broadcast::Sender<DeploymentEvent>,
+    writer: Option<mpsc::Sender<WriterMessage>>,
+}
+
+/// Work for the thread that persists events
+enum WriterMessage {
+    /// Persist and publish an event that happened at `timestamp`
+    Record(EventKind, u64),
+    /// Reply once every earlier message has been handled
+    Flush(mpsc::Sender<()>),
+}
+
+impl EventLog {
+    /// Open the log persisted at `path`, loading the events already recorded there
+    ///
+    /// A final line cut short by a crash is removed from the file. Any other unreadable
+    /// line or gap in the sequence is an error, since numbering can't safely continue.
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let events = Arc::new(Mutex::new(load_events(&path)?));
+        let sender = broadcast::channel(EVENT_CHANNEL_CAPACITY).0;
+        let (writer, messages) = mpsc::channel();
+
+        let (thread_events, thread_sender) = (events.clone(), sender.clone());
+        std::thread::Builder::new()
+            .name("event-log-writer".to_string())
+            .spawn(move || write_events(&path, &thread_events, &thread_sender, messages))
+            .map_err(|e| format!("Failed to start the event log writer: {}", e))?;
+
+        Ok(Self {
+            events,
+            sender,
+            writer: Some(writer),
+        })
+    }
+
+    /// Create a log that is kept in memory only
+    pub fn in_memory() -> Self {
+        Self {
+            events: Arc::new(Mutex::new(Vec::new())),
+            sender: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            writer: None,
+        }
+    }
+
+    /// Record an event, persisting it before it is broadcast to subscribers
+    ///
+    /// Persisted logs write and fsync on a dedicated thread, so this never blocks the
+    /// async runtime. The event gets its sequence number only once it is on disk; an event
+    /// that can't be written is dropped with an error, so the sequence never has gaps.
+    pub fn record(&self, kind: EventKind) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+
+        match &self.writer {
+            Some(writer) => {
+                if writer.send(WriterMessage::Record(kind, timestamp)).is_err() {
+                    tracing::error!("Event log writer has stopped; dropping deployment event");
+                }
+            }
+            None => {
+                publish(&self.events, &self.sender, kind, timestamp, |_| Ok(()));
+            }
+        }
+    }
+
+    /// Wait until every event recorded so far has been persisted or dropped
+    ///
+    /// This blocks, so async callers should run it with `spawn_blocking`.
+    pub fn flush(&self) {
+        if let Some(writer) = &self.writer {
+            let (done, wait) = mpsc::channel();
+            if writer.send(WriterMessage::Flush(done)).is_ok() {
+                let _ = wait.recv();
+            }
+        }
+    }
+
+    /// Events with a sequence number greater than `since`, in order
+    pub fn since(&self, since: u64) -> Vec<DeploymentEvent> {
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let start = events.partition_point(|event| event.seq <= since);
+        events[start..].to_vec()
+    }
+
+    /// Events after `since` together with a subscription to every later event
+    ///
+    /// Both are taken under the same lock, so together they miss nothing. A subscriber
+    /// that lags can call this again with the last sequence number it handled, and should
+    /// skip events it has already seen.
+    pub fn subscribe_since(
+        &self,
+        since: u64,
+    ) -> (Vec<DeploymentEvent>, broadcast::Receiver<DeploymentEvent>) {
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let start = events.partition_point(|event| event.seq <= since);
+        (events[start..].to_vec(), self.sender.subscribe())
+    }
+}
+
+/// Persist and publish events until every [`EventLog`] handle is dropped
+fn write_events(
+    path: &Path,
+    events: &Mutex<Vec<DeploymentEvent>>,
+    sender: &broadcast::Sender<DeploymentEvent>,
+    messages: mpsc::Receiver<WriterMessage>,
+) {
+    for message in messages {
+        match message {
+            WriterMessage::Record(kind, timestamp) => {
+                publish(events, sender, kind, timestamp, |event| {
+                    append_event(path, event)
+                });
+            }
+            WriterMessage::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+/// Number an event, run `persist` on it and, if that succeeds, add it to the history and
+/// broadcast it
+///
+/// Only the writer thread (or the caller, for in-memory logs) publishes, so the number
+/// taken before persisting is still the next one afterwards.
+fn publish(
+    events: &Mutex<Vec<DeploymentEvent>>,
+    sender: &broadcast::Sender<DeploymentEvent>,
+    kind: EventKind,
+    timestamp: u64,
+    persist: impl FnOnce(&DeploymentEvent) -> Result<(), String>,
+) {
+    let seq = events
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .last()
+        .map_or(1, |last| last.seq + 1);
+    let event = DeploymentEvent {
+        seq,
+        timestamp,
+        kind,
+    };
+
+    if let Err(e) = persist(&event) {
+        tracing::error!("Dropping deployment event {}: {}", seq, e);
+        return;
+    }
+
+    let mut events = events.lock().unwrap_or_else(|e| e.into_inner());
+    events.push(event.clone());
+    // Nobody listening is fine; the event is still in the history
+    let _ = sender.send(event);
+}
+
+/// Read the events persisted at `path`, checking that they are numbered without gaps
+fn load_events(path: &Path) -> Result<Vec<DeploymentEvent>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let mut events: Vec<DeploymentEvent> = Vec::new();
+    let mut offset = 0;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let event = match serde_json::from_str::<DeploymentEvent>(line) {
+            Ok(event) => event,
+            // Every append ends with a newline, so a final line without one is a torn write
+            Err(e) if !line.ends_with('\n') => {
+                tracing::warn!(
+                    "Removing incomplete last event from {}: {}",
+                    path.display(),
+                    e
+                );
+                truncate(path, line_start as u64)?;
+                break;
+            }
+            Err(e) => {
+                return Err(format!(
+                    "Unreadable event on line {} of {}: {}",
+                    index + 1,
+                    path.display(),
+                    e
+                ));
+            }
+        };
+
+        let expected = events.last().map_or(1, |last| last.seq + 1);
+        if event.seq != expected {
+            return Err(format!(
+                "Event on line {} of {} has sequence number {}, expected {}",
+                index + 1,
+                path.display(),
+                event.seq,
+                expected
+            ));
+        }
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Cut the file at `path` down to `len` bytes
+fn truncate(path: &Path, len: u64) -> Result<(), String> {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_len(len).and_then(|()| file.sync_data()))
+        .map_err(|e| format!("Failed to truncate {}: {}", path.display(), e))
+}
+
+/// Append one event to the JSON lines file at `path`
+fn append_event(path: &Path, event: &DeploymentEvent) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let mut line =
+        serde_json::to_string(event).map_err(|e| format!("Failed to serialize event: {}", e))?;
+    line.push('\n');
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(line.as_bytes())
+        .and_then(|()| file.sync_data())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "avail-orbit-raas-events-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("events.jsonl")
+    }
+
+    fn started() -> EventKind {
+        EventKind::DeploymentStarted
+    }
+
+    #[test]
+    fn numbering_continues_after_reopen() {
+        let path = log_path("reopen");
+        let log = EventLog::open(path.clone()).unwrap();
+        log.record(started());
+        log.record(EventKind::DeploymentSucceeded);
+        log.flush();
+        assert_eq!(log.since(0).len(), 2);
+        drop(log);
+
+        let log = EventLog::open(path.clone()).unwrap();
+        log.record(started());
+        log.flush();
+        let seqs: Vec<u64> = log.since(0).iter().map(|event| event.seq).collect();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(seqs, [1, 2, 3]);
+    }
+
+    #[test]
+    fn failed_append_does_not_use_a_sequence_number() {
+        let events = Mutex::new(Vec::new());
+        let sender = broadcast::channel(EVENT_CHANNEL_CAPACITY).0;
+        publish(&events, &sender, started(), 0, |_| Err("disk full".into()));
+        publish(&events, &sender, started(), 0, |_| Ok(()));
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].seq, 1);
+    }
+
+    #[test]
+    fn open_removes_a_torn_last_line() {
+        let path = log_path("torn");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            "{\"seq\":1,\"timestamp\":0,\"type\":\"deployment_started\"}\n{\"seq\":2,\"tim",
+        )
+        .unwrap();
+
+        let log = EventLog::open(path.clone()).unwrap();
+        log.record(EventKind::DeploymentSucceeded);
+        log.flush();
+        let seqs: Vec<u64> = log.since(0).iter().map(|event| event.seq).collect();
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(seqs, [1, 2]);
+        assert_eq!(lines, 2);
+    }
+
+    #[test]
+    fn open_rejects_corrupt_or_out_of_sequence_logs() {
+        let path = log_path("corrupt");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        std::fs::write(
+            &path,
+            "not json\n{\"seq\":1,\"timestamp\":0,\"type\":\"deployment_started\"}\n",
+        )
+        .unwrap();
+        let corrupt = EventLog::open(path.clone()).err().unwrap();
+
+        std::fs::write(
+            &path,
+            "{\"seq\":1,\"timestamp\":0,\"type\":\"deployment_started\"}\n\
+             {\"seq\":3,\"timestamp\":0,\"type\":\"deployment_started\"}\n",
+        )
+        .unwrap();
+        let gap = EventLog::open(path.clone()).err().unwrap();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+        assert!(
+            corrupt.contains("Unreadable event on line 1"),
+            "{}",
+            corrupt
+        );
+        assert!(gap.contains("expected 2"), "{}", gap);
+    }
+
+    #[test]
+    fn subscribers_receive_events_after_the_history() {
+        let log = EventLog::in_memory();
+        log.record(started());
+        let (history, mut receiver) = log.subscribe_since(0);
+        log.record(EventKind::DeploymentSucceeded);
+
+        assert_eq!(history.len(), 1);
+        let live = receiver.try_recv().unwrap();
+        assert_eq!(live.seq, 2);
+        assert_eq!(live.kind, EventKind::DeploymentSucceeded);
+    }
+}
@@ -4,10 +4,72 @@
 //! These functions only accept public metadata as input, with no private keys or sensitive data.
 
 use crate::OrbitContext;
-use crate::deployment::{restart_containers, update_metadata, update_rollup_bridge};
-use crate::types::RollupMetadata;
+use crate::deployment::{
+    avail_stats, batch_lag, contract_addresses, deposit_eth as send_deposit,
+    export_node_config as read_redacted_node_config, force_batch_post as flush_batches,
+    restart_containers, set_batch_max_items as set_node_batch_max_items,
+    set_block_gas_limit as set_chain_block_gas_limit, set_min_base_fee as set_chain_min_base_fee,
+    set_speed_limit as set_chain_speed_limit, stop_containers, store_node_config_export,
+    update_metadata, update_rollup_bridge,
+};
+use crate::events::EventKind;
+use crate::log_export::export_logs as upload_logs;
+use crate::resources::collect_resource_usage;
+use crate::selftest::run_self_test;
+use crate::types::{JobSummary, LogExportRequest, RollupMetadata};
 use blueprint_sdk::extract::Context;
 use blueprint_sdk::tangle::extract::{TangleArg, TangleResult};
+use std::time::Instant;
+
+/// Largest node config export returned inline as a job result
+const MAX_NODE_CONFIG_RESULT_BYTES: usize = 32 * 1024;
+/// Deployment log lines returned by [`get_logs`] when no count is given
+const DEFAULT_LOG_TAIL_LINES: u32 = 50;
+/// Result of any job called while the operator is draining for maintenance
+const DRAINING_MESSAGE: &str = "Operator is draining for maintenance and not accepting new jobs";
+
+/// Serialize a job summary as the job result, timing it from `started`
+///
+/// The outcome is also recorded in the event log under the job's name.
+fn summary_result(
+    ctx: &OrbitContext,
+    job: &str,
+    mut summary: JobSummary,
+    started: Instant,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    summary.duration_ms = started.elapsed().as_millis() as u64;
+    ctx.events.record(EventKind::JobCompleted {
+        job: job.to_string(),
+        success: summary.success,
+        message: summary.message.clone(),
+    });
+    match serde_json::to_string(&summary) {
+        Ok(json) => Ok(TangleResult(json)),
+        Err(_) => Ok(TangleResult(summary.message)),
+    }
+}
+
+/// Record and return a failed summary for a job called while the operator is draining
+pub fn draining_result(
+    ctx: &OrbitContext,
+    job: &str,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    summary_result(
+        ctx,
+        job,
+        failure(DRAINING_MESSAGE.to_string()),
+        Instant::now(),
+    )
+}
+
+/// Build a summary for a job that failed with `message`
+fn failure(message: String) -> JobSummary {
+    JobSummary {
+        success: false,
+        message,
+        ..Default::default()
+    }
+}
 
 /// Modify rollup metadata
 ///
@@ -17,46 +79,387 @@ pub async fn modify_rollup_metadata(
     Context(ctx): Context<OrbitContext>,
     TangleArg(metadata): TangleArg<RollupMetadata>,
 ) -> Result<TangleResult<String>, blueprint_sdk::Error> {
-    match update_metadata(&ctx, &metadata).await {
-        Ok(_) => Ok(TangleResult(
-            "Rollup metadata successfully updated".to_string(),
-        )),
+    let started = Instant::now();
+    let summary = match update_metadata(&ctx, &metadata).await {
+        Ok(_) => JobSummary {
+            success: true,
+            message: "Rollup metadata successfully updated".to_string(),
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to update rollup metadata: {}", e)),
+    };
+    summary_result(&ctx, "modify_rollup_metadata", summary, started)
+}
+
+/// Restart the rollup
+///
+/// This job restarts the rollup with the current configuration and reports which
+/// containers were restarted. No private data is needed for this operation.
+pub async fn restart_rollup(
+    Context(ctx): Context<OrbitContext>,
+    _: TangleArg<()>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match restart_containers(&ctx).await {
+        Ok(containers) => JobSummary {
+            success: true,
+            message: "Rollup successfully restarted".to_string(),
+            containers,
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to restart rollup: {}", e)),
+    };
+    summary_result(&ctx, "restart_rollup", summary, started)
+}
+
+/// Stop the rollup
+///
+/// This job stops the rollup's containers without removing them or their data, so the
+/// rollup can be brought back with the restart job. Stopping a rollup that isn't running
+/// succeeds without doing anything.
+pub async fn stop_rollup(
+    Context(ctx): Context<OrbitContext>,
+    _: TangleArg<()>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match stop_containers(&ctx).await {
+        Ok(containers) if containers.is_empty() => JobSummary {
+            success: true,
+            message: "Rollup is not running; nothing to stop".to_string(),
+            ..Default::default()
+        },
+        Ok(containers) => JobSummary {
+            success: true,
+            message: "Rollup successfully stopped".to_string(),
+            containers,
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to stop rollup: {}", e)),
+    };
+    summary_result(&ctx, "stop_rollup", summary, started)
+}
+
+/// Update the token bridge
+///
+/// This job updates the token bridge configuration, redeploys it, and reports any new
+/// bridge addresses. Private keys are managed by the operator and not exposed in job
+/// parameters.
+pub async fn update_bridge(
+    Context(ctx): Context<OrbitContext>,
+    _: TangleArg<()>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match update_rollup_bridge(&ctx).await {
+        Ok(addresses) => JobSummary {
+            success: true,
+            message: "Token bridge successfully updated".to_string(),
+            addresses,
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to update token bridge: {}", e)),
+    };
+    summary_result(&ctx, "update_bridge", summary, started)
+}
+
+/// Set the batch poster's maximum items per batch
+///
+/// This job updates the node config and restarts the rollup so throughput can be
+/// tuned on a live chain. The value must be non-zero and bounded.
+pub async fn set_batch_max_items(
+    Context(ctx): Context<OrbitContext>,
+    TangleArg(max_items): TangleArg<u64>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match set_node_batch_max_items(&ctx, max_items).await {
+        Ok(previous) => JobSummary {
+            success: true,
+            message: format!(
+                "Batch poster max items updated from {} to {}",
+                previous.map_or("default".to_string(), |previous| previous.to_string()),
+                max_items
+            ),
+            containers: ctx.status.lock().await.container_ids.clone(),
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to update batch poster max items: {}", e)),
+    };
+    summary_result(&ctx, "set_batch_max_items", summary, started)
+}
+
+/// Set the chain's minimum L2 base fee
+///
+/// This job submits an ArbOwner `setMinimumL2BaseFee` transaction with the operator's
+/// owner key, so gas pricing can be tuned to demand. The fee is in wei and bounded.
+pub async fn set_min_base_fee(
+    Context(ctx): Context<OrbitContext>,
+    TangleArg(fee_wei): TangleArg<u64>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match set_chain_min_base_fee(&ctx, fee_wei).await {
+        Ok(()) => JobSummary {
+            success: true,
+            message: format!("Minimum L2 base fee set to {} wei", fee_wei),
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to set minimum L2 base fee: {}", e)),
+    };
+    summary_result(&ctx, "set_min_base_fee", summary, started)
+}
+
+/// Set the L2 speed limit
+///
+/// This job submits an ArbOwner `setSpeedLimit` transaction with the operator's owner
+/// key. The limit is in gas per second and bounded.
+pub async fn set_speed_limit(
+    Context(ctx): Context<OrbitContext>,
+    TangleArg(limit): TangleArg<u64>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match set_chain_speed_limit(&ctx, limit).await {
+        Ok(()) => JobSummary {
+            success: true,
+            message: format!("Speed limit set to {} gas per second", limit),
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to set speed limit: {}", e)),
+    };
+    summary_result(&ctx, "set_speed_limit", summary, started)
+}
+
+/// Set the L2 block gas limit
+///
+/// This job submits an ArbOwner `setMaxTxGasLimit` transaction with the operator's owner
+/// key. The limit is bounded so contract deployments still fit in a block.
+pub async fn set_block_gas_limit(
+    Context(ctx): Context<OrbitContext>,
+    TangleArg(limit): TangleArg<u64>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match set_chain_block_gas_limit(&ctx, limit).await {
+        Ok(()) => JobSummary {
+            success: true,
+            message: format!("Block gas limit set to {}", limit),
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to set block gas limit: {}", e)),
+    };
+    summary_result(&ctx, "set_block_gas_limit", summary, started)
+}
+
+/// Force the batch poster to post pending batches
+///
+/// This job restarts the sequencer so its batch poster posts pending batches, e.g. after
+/// parent chain congestion stalled posting, and reports how many batches were posted.
+pub async fn force_batch_post(
+    Context(ctx): Context<OrbitContext>,
+    _: TangleArg<()>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match flush_batches(&ctx).await {
+        Ok(flushed) => JobSummary {
+            success: true,
+            message: format!("Posted {} pending batches", flushed),
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to force batch post: {}", e)),
+    };
+    summary_result(&ctx, "force_batch_post", summary, started)
+}
+
+/// Export the deployment logs to object storage
+///
+/// This job uploads the deployment logs, and optionally each step's script output, to the
+/// operator's S3 fallback bucket for off-host retention, and returns the object URL.
+pub async fn export_logs(
+    Context(ctx): Context<OrbitContext>,
+    TangleArg(request): TangleArg<LogExportRequest>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match upload_logs(&ctx, &request).await {
+        Ok(url) => JobSummary {
+            success: true,
+            message: format!("Exported deployment logs to {}", url),
+            url: Some(url),
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to export logs: {}", e)),
+    };
+    summary_result(&ctx, "export_logs", summary, started)
+}
+
+/// Get the deployment logs
+///
+/// This job returns the last `tail` deployment log lines, 50 if not given, joined by
+/// newlines, for operators who can reach the service over Tangle but not over HTTP.
+pub async fn get_logs(
+    Context(ctx): Context<OrbitContext>,
+    TangleArg(tail): TangleArg<Option<u32>>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let tail = tail.unwrap_or(DEFAULT_LOG_TAIL_LINES) as usize;
+    let status = ctx.status.lock().await;
+    let start = status.logs.len().saturating_sub(tail);
+    Ok(TangleResult(status.logs[start..].join("\n")))
+}
+
+/// Get the deployed contract addresses
+///
+/// This job returns the rollup and bridge contract addresses as a JSON object keyed by
+/// contract name, so on-chain consumers can build transactions against the rollup.
+pub async fn get_contract_addresses(
+    Context(ctx): Context<OrbitContext>,
+    _: TangleArg<()>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    match contract_addresses(&ctx).await {
+        Ok(addresses) => match serde_json::to_string(&addresses) {
+            Ok(json) => Ok(TangleResult(json)),
+            Err(e) => Ok(TangleResult(format!(
+                "Failed to serialize contract addresses: {}",
+                e
+            ))),
+        },
         Err(e) => Ok(TangleResult(format!(
-            "Failed to update rollup metadata: {}",
+            "Failed to get contract addresses: {}",
             e
         ))),
     }
 }
 
-/// Restart the rollup
+/// Get Avail DA submission statistics
 ///
-/// This job restarts the rollup with the current configuration.
-/// No private data is needed for this operation.
-pub async fn restart_rollup(
+/// This job scrapes the node's metrics endpoint and returns the Avail submission counters
+/// as JSON. It fails with a clear message if the node's metrics endpoint is disabled.
+pub async fn get_avail_stats(
     Context(ctx): Context<OrbitContext>,
     _: TangleArg<()>,
 ) -> Result<TangleResult<String>, blueprint_sdk::Error> {
-    match restart_containers(&ctx).await {
-        Ok(_) => Ok(TangleResult("Rollup successfully restarted".to_string())),
-        Err(e) => Ok(TangleResult(format!("Failed to restart rollup: {}", e))),
+    match avail_stats(&ctx).await {
+        Ok(stats) => match serde_json::to_string(&stats) {
+            Ok(json) => Ok(TangleResult(json)),
+            Err(e) => Ok(TangleResult(format!(
+                "Failed to serialize Avail stats: {}",
+                e
+            ))),
+        },
+        Err(e) => Ok(TangleResult(format!("Failed to get Avail stats: {}", e))),
     }
 }
 
-/// Update the token bridge
+/// Get the parent chain batch posting lag
 ///
-/// This job updates the token bridge configuration and redeploys it.
-/// Private keys are managed by the operator and not exposed in job parameters.
-pub async fn update_bridge(
+/// This job returns how many rollup blocks, and how many seconds of them, have not yet been
+/// posted to the parent chain as JSON, flagging a lag above the operator's threshold.
+pub async fn get_batch_lag(
     Context(ctx): Context<OrbitContext>,
     _: TangleArg<()>,
 ) -> Result<TangleResult<String>, blueprint_sdk::Error> {
-    match update_rollup_bridge(&ctx).await {
-        Ok(_) => Ok(TangleResult(
-            "Token bridge successfully updated".to_string(),
-        )),
+    match batch_lag(&ctx).await {
+        Ok(lag) => match serde_json::to_string(&lag) {
+            Ok(json) => Ok(TangleResult(json)),
+            Err(e) => Ok(TangleResult(format!(
+                "Failed to serialize batch lag: {}",
+                e
+            ))),
+        },
+        Err(e) => Ok(TangleResult(format!("Failed to get batch lag: {}", e))),
+    }
+}
+
+/// Run the control plane self-test
+///
+/// This job checks the required tools, Docker and Tangle connectivity, working directory
+/// writability, and config validity without deploying, and returns a pass/fail report
+/// per check as JSON.
+pub async fn self_test(
+    Context(ctx): Context<OrbitContext>,
+    _: TangleArg<()>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let report = run_self_test(&ctx).await;
+    match serde_json::to_string(&report) {
+        Ok(json) => Ok(TangleResult(json)),
         Err(e) => Ok(TangleResult(format!(
-            "Failed to update token bridge: {}",
+            "Failed to serialize self-test report: {}",
             e
         ))),
     }
 }
+
+/// Get the resource usage of the rollup's containers
+///
+/// This job returns CPU, memory, and disk usage per container and in total as JSON.
+/// Containers that can't be sampled are listed under `errors` instead of failing the job.
+pub async fn get_resource_usage(
+    Context(ctx): Context<OrbitContext>,
+    _: TangleArg<()>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    match collect_resource_usage(&ctx).await {
+        Ok(usage) => match serde_json::to_string(&usage) {
+            Ok(json) => Ok(TangleResult(json)),
+            Err(e) => Ok(TangleResult(format!(
+                "Failed to serialize resource usage: {}",
+                e
+            ))),
+        },
+        Err(e) => Ok(TangleResult(format!("Failed to get resource usage: {}", e))),
+    }
+}
+
+/// Export the node config
+///
+/// This job returns the running chain's `nodeConfig.json` with all secrets redacted, for
+/// audit and recovery. If it is too large for a job result, it is stored on the operator's
+/// host and the result gives its path instead.
+pub async fn export_node_config(
+    Context(ctx): Context<OrbitContext>,
+    _: TangleArg<()>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let node_config = match read_redacted_node_config(&ctx).await {
+        Ok(node_config) => node_config,
+        Err(e) => {
+            return Ok(TangleResult(format!("Failed to export node config: {}", e)));
+        }
+    };
+
+    let json = match serde_json::to_string(&node_config) {
+        Ok(json) => json,
+        Err(e) => {
+            return Ok(TangleResult(format!(
+                "Failed to serialize node config: {}",
+                e
+            )));
+        }
+    };
+    if json.len() <= MAX_NODE_CONFIG_RESULT_BYTES {
+        return Ok(TangleResult(json));
+    }
+
+    match store_node_config_export(&ctx, &json).await {
+        Ok(path) => Ok(TangleResult(format!(
+            "Node config is too large for a job result ({} bytes), stored at {}",
+            json.len(),
+            path.display()
+        ))),
+        Err(e) => Ok(TangleResult(format!("Failed to export node config: {}", e))),
+    }
+}
+
+/// Deposit ETH into the rollup
+///
+/// This job deposits the given decimal ether amount from the operator's deployer account
+/// through the rollup's inbox on the parent chain, and returns the transaction hash. Only
+/// the amount is passed; the deployer key stays with the operator.
+pub async fn deposit_eth(
+    Context(ctx): Context<OrbitContext>,
+    TangleArg(amount): TangleArg<String>,
+) -> Result<TangleResult<String>, blueprint_sdk::Error> {
+    let started = Instant::now();
+    let summary = match send_deposit(&ctx, &amount).await {
+        Ok(tx_hash) => JobSummary {
+            success: true,
+            message: format!("Deposited {} ETH into the rollup", amount.trim()),
+            tx_hash: Some(tx_hash),
+            ..Default::default()
+        },
+        Err(e) => failure(format!("Failed to deposit ETH: {}", e)),
+    };
+    summary_result(&ctx, "deposit_eth", summary, started)
+}
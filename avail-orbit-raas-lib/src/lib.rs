@@ -1,10 +1,27 @@
+use events::EventLog;
+use persistence::{FileStatusStore, StatusStore};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Mutex;
 
 // Module declarations
+pub mod artifact_cache;
+pub mod avail;
+pub mod cleanup;
 pub mod config;
 pub mod deployment;
+pub mod diagnostics;
+pub mod events;
 pub mod jobs;
+pub mod log_export;
+pub mod metrics;
+pub mod persistence;
+pub mod preflight;
+pub mod resources;
+pub mod rpc;
+pub mod selftest;
+pub mod startup;
 pub mod types;
 pub mod util;
 
@@ -18,19 +35,70 @@ pub struct OrbitContext {
     pub status: Arc<Mutex<DeploymentStatus>>,
     /// Operator configuration with private keys (not exposed to blockchain)
     pub operator_config: Arc<Mutex<OperatorConfig>>,
+    /// Set while draining for maintenance, when new jobs are rejected
+    pub draining: Arc<AtomicBool>,
+    /// Where the deployment status is persisted
+    pub store: Arc<dyn StatusStore>,
+    /// Sequenced log of deployment events
+    pub events: Arc<EventLog>,
 }
 
 impl OrbitContext {
-    pub fn new(operator_config: OperatorConfig) -> Self {
-        Self {
+    /// Create a context persisting the status to the configured status file
+    ///
+    /// Fails if the persisted event log can't be read.
+    pub fn new(operator_config: OperatorConfig) -> Result<Self, String> {
+        let store = Arc::new(FileStatusStore::new(
+            operator_config.deployment.status_path(),
+        ));
+        Self::with_store(operator_config, store)
+    }
+
+    /// Create a context persisting the status to `store`
+    ///
+    /// Events are still persisted to the configured events file.
+    pub fn with_store(
+        operator_config: OperatorConfig,
+        store: Arc<dyn StatusStore>,
+    ) -> Result<Self, String> {
+        let events = Arc::new(EventLog::open(operator_config.deployment.events_path())?);
+        Ok(Self {
             status: Arc::new(Mutex::new(DeploymentStatus::default())),
             operator_config: Arc::new(Mutex::new(operator_config)),
-        }
+            draining: Arc::new(AtomicBool::new(false)),
+            store,
+            events,
+        })
+    }
+
+    /// Get a copy of the operator's deployment settings
+    pub async fn deployment_config(&self) -> DeploymentConfig {
+        self.operator_config.lock().await.deployment.clone()
+    }
+
+    /// Get the deployment working directory
+    pub async fn working_dir(&self) -> PathBuf {
+        self.operator_config
+            .lock()
+            .await
+            .deployment
+            .working_dir
+            .clone()
+    }
+
+    /// Whether new jobs are being rejected for maintenance
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Start or stop rejecting new jobs; jobs already running are unaffected
+    pub fn set_draining(&self, draining: bool) {
+        self.draining.store(draining, Ordering::SeqCst);
     }
 
     /// Add a log message to the deployment status
     pub async fn log(&self, message: &str) {
         let mut status = self.status.lock().await;
-        status.logs.push(message.to_string());
+        status.push_log(message);
     }
 }
@@ -0,0 +1,235 @@
+//! Export of deployment logs to S3-compatible object storage
+//!
+//! Uploads the deployment logs, and optionally the captured script output of each step, as
+//! a JSON document to the bucket configured for the S3 fallback, so they can be retained
+//! off-host. Uploads go through the `aws` CLI, which also reaches S3-compatible stores
+//! through `S3_ENDPOINT_URL`.
+
+use crate::OrbitContext;
+use crate::config::OperatorConfig;
+use crate::types::{DeploymentStep, LogExportRequest};
+use crate::util::{
+    combined_output, create_private_dir, tail_bytes, validate_s3_prefix, write_private_file,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::process::Command;
+
+/// Bytes of `aws` output included in error messages
+const ERROR_OUTPUT_TAIL_BYTES: usize = 2 * 1024;
+
+/// Document uploaded by [`export_logs`]
+#[derive(Serialize)]
+struct LogExport<'a> {
+    /// When the export was made, in seconds since the Unix epoch
+    exported_at: u64,
+    /// Deployment log lines
+    logs: &'a [String],
+    /// Captured script output of each step, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    step_outputs: Option<&'a BTreeMap<DeploymentStep, String>>,
+}
+
+/// Bucket and credentials the export is uploaded with
+struct S3Target {
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+    endpoint_url: Option<String>,
+}
+
+impl S3Target {
+    /// Take the S3 fallback settings, failing if any of them is missing
+    fn from_config(config: &OperatorConfig) -> Result<Self, String> {
+        let required = |value: &Option<String>, name: &str| {
+            value
+                .clone()
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| format!("{} is not set", name))
+        };
+        Ok(Self {
+            bucket: required(&config.fallback_s3_bucket, "FALLBACKS3_BUCKET")?,
+            region: required(&config.fallback_s3_region, "FALLBACKS3_REGION")?,
+            access_key: required(&config.fallback_s3_access_key, "FALLBACKS3_ACCESS_KEY")?,
+            secret_key: required(&config.fallback_s3_secret_key, "FALLBACKS3_SECRET_KEY")?,
+            endpoint_url: config.deployment.s3_endpoint_url.clone(),
+        })
+    }
+
+    /// Create an `aws` command authenticated for this target
+    ///
+    /// Credentials are passed in the environment so they never appear on the command line.
+    fn aws(&self) -> Command {
+        let mut aws = Command::new("aws");
+        aws.env("AWS_ACCESS_KEY_ID", &self.access_key)
+            .env("AWS_SECRET_ACCESS_KEY", &self.secret_key)
+            .env("AWS_DEFAULT_REGION", &self.region)
+            .env_remove("AWS_SESSION_TOKEN")
+            .env_remove("AWS_PROFILE");
+        if let Some(endpoint_url) = &self.endpoint_url {
+            aws.args(["--endpoint-url", endpoint_url]);
+        }
+        aws
+    }
+
+    /// URL of the object stored under `key`
+    fn object_url(&self, key: &str) -> String {
+        match &self.endpoint_url {
+            Some(endpoint_url) => format!(
+                "{}/{}/{}",
+                endpoint_url.trim_end_matches('/'),
+                self.bucket,
+                key
+            ),
+            None => format!(
+                "https://{}.s3.{}.amazonaws.com/{}",
+                self.bucket, self.region, key
+            ),
+        }
+    }
+}
+
+/// Upload the deployment logs to the S3 fallback bucket and return the object URL
+///
+/// The object is stored under `logs/` below the rollup's S3 object prefix, named
+/// `request.destination` if set. The credentials and bucket access are checked
+/// before anything is uploaded.
+pub async fn export_logs(
+    context: &OrbitContext,
+    request: &LogExportRequest,
+) -> Result<String, String> {
+    let operator_config = context.operator_config.lock().await.clone();
+    let target = S3Target::from_config(&operator_config)?;
+
+    let exported_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("System clock is before the Unix epoch: {}", e))?
+        .as_secs();
+    let (document, key) = {
+        let status = context.status.lock().await;
+        let chain_id = status
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.chain_id)
+            .unwrap_or_default();
+        let name = match request
+            .destination
+            .as_deref()
+            .filter(|name| !name.is_empty())
+        {
+            Some(name) => log_export_name(name)?,
+            None => format!("deployment-logs-{}.json", exported_at),
+        };
+        let key = format!(
+            "{}logs/{}",
+            operator_config.fallback_s3_object_prefix(chain_id),
+            name
+        );
+        let export = LogExport {
+            exported_at,
+            logs: &status.logs,
+            step_outputs: request.include_step_outputs.then_some(&status.step_outputs),
+        };
+        let document = serde_json::to_vec_pretty(&export)
+            .map_err(|e| format!("Failed to serialize logs: {}", e))?;
+        (document, key)
+    };
+    validate_s3_prefix(&key)?;
+
+    check_bucket_access(&target).await?;
+
+    let exports_dir = context.working_dir().await.join("exports");
+    create_private_dir(&exports_dir)
+        .map_err(|e| format!("Failed to create exports directory: {}", e))?;
+    let staged = exports_dir.join(format!("deployment-logs-{}.json", exported_at));
+    write_private_file(&staged, &document)
+        .map_err(|e| format!("Failed to stage log export: {}", e))?;
+
+    let uri = format!("s3://{}/{}", target.bucket, key);
+    let result = target
+        .aws()
+        .args([
+            "s3",
+            "cp",
+            "--only-show-errors",
+            "--content-type",
+            "application/json",
+        ])
+        .arg(&staged)
+        .arg(&uri)
+        .output()
+        .await;
+    let _ = std::fs::remove_file(&staged);
+
+    let output = result.map_err(|e| format!("Failed to run aws: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to upload logs to {}: {}",
+            uri,
+            tail_bytes(&combined_output(&output), ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+
+    let url = target.object_url(&key);
+    context
+        .log(&format!("Exported deployment logs to {}", url))
+        .await;
+    Ok(url)
+}
+
+/// Check a requested export destination, a name relative to the rollup's `logs/` prefix
+///
+/// Callers can't choose where in the bucket the export goes, so `..` segments, absolute
+/// names and names of directories are rejected.
+fn log_export_name(name: &str) -> Result<String, String> {
+    if name.starts_with('/') || name.ends_with('/') {
+        return Err(format!(
+            "Log export destination {} must be a relative object name",
+            name
+        ));
+    }
+    validate_s3_prefix(name).map_err(|e| format!("Invalid log export destination: {}", e))?;
+    Ok(name.to_string())
+}
+
+/// Check that the credentials are valid and can access the bucket
+async fn check_bucket_access(target: &S3Target) -> Result<(), String> {
+    let output = target
+        .aws()
+        .args(["s3api", "head-bucket", "--bucket", &target.bucket])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run aws (is the AWS CLI installed?): {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "S3 credentials can't access bucket {}: {}",
+            target.bucket,
+            tail_bytes(&combined_output(&output), ERROR_OUTPUT_TAIL_BYTES)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_name_stays_below_logs_prefix() {
+        assert_eq!(
+            log_export_name("run-1/logs.json").unwrap(),
+            "run-1/logs.json"
+        );
+        for name in [
+            "../orbit/42/state.json",
+            "a/../../b.json",
+            "/orbit/42/state.json",
+            "dir/",
+            "./logs.json",
+        ] {
+            assert!(log_export_name(name).is_err(), "{} was accepted", name);
+        }
+    }
+}
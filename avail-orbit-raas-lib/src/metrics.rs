@@ -0,0 +1,320 @@
+//! Metrics for Avail Orbit RaaS
+//!
+//! Reads the Nitro node's Prometheus metrics endpoint and summarizes the Avail DA counters,
+//! and renders the service's own deployment metrics in Prometheus text format.
+
+use crate::types::{DeploymentStatus, DeploymentStep};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Avail DA submission statistics scraped from the node
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct AvailStats {
+    /// Total bytes submitted to Avail
+    pub bytes_submitted: Option<f64>,
+    /// Number of submissions to Avail
+    pub submission_count: Option<f64>,
+    /// Number of failed submissions to Avail
+    pub submission_failures: Option<f64>,
+    /// Every Avail-related metric except histogram buckets, summed across label sets
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// Fetch Avail DA statistics from the node's Prometheus endpoint at `metrics_url`
+///
+/// See [`PortAllocation::metrics_url`](crate::config::PortAllocation::metrics_url).
+pub async fn fetch_avail_stats(
+    client: &reqwest::Client,
+    metrics_url: &str,
+) -> Result<AvailStats, String> {
+    let response = client.get(metrics_url).send().await.map_err(|e| {
+        format!(
+            "Node metrics endpoint unavailable at {} (is metrics enabled?): {}",
+            metrics_url, e
+        )
+    })?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Node metrics endpoint returned {}",
+            response.status()
+        ));
+    }
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read node metrics: {}", e))?;
+    Ok(parse_avail_stats(&body))
+}
+
+/// Summarize the Avail metrics in Prometheus text exposition format
+///
+/// Histogram buckets are cumulative, so they are skipped. Counts come from counters
+/// (`_total`) and histogram counts (`_count`); bytes from byte counters (`_total`) and
+/// byte histogram sums (`_sum`).
+pub fn parse_avail_stats(metrics_text: &str) -> AvailStats {
+    let mut stats = AvailStats::default();
+
+    for (name, value) in parse_prometheus_text(metrics_text) {
+        if !name.to_lowercase().contains("avail") || name.ends_with("_bucket") {
+            continue;
+        }
+        *stats.metrics.entry(name).or_default() += value;
+    }
+
+    for (name, value) in &stats.metrics {
+        let name = name.to_lowercase();
+        let is_count = name.ends_with("_total") || name.ends_with("_count");
+        let is_bytes = name.contains("bytes") || name.contains("size");
+        let target = if name.contains("fail") || name.contains("error") {
+            if !is_count {
+                continue;
+            }
+            &mut stats.submission_failures
+        } else if is_bytes {
+            if !(name.ends_with("_total") || name.ends_with("_sum")) {
+                continue;
+            }
+            &mut stats.bytes_submitted
+        } else if is_count {
+            &mut stats.submission_count
+        } else {
+            continue;
+        };
+        *target = Some(target.unwrap_or_default() + value);
+    }
+
+    stats
+}
+
+/// Parse `(metric name, value)` samples from Prometheus text, dropping labels
+pub fn parse_prometheus_text(metrics_text: &str) -> Vec<(String, f64)> {
+    metrics_text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let name_end = line.find(['{', ' '])?;
+            let name = &line[..name_end];
+            let rest = match line[name_end..].strip_prefix('{') {
+                Some(labelled) => &labelled[labelled.find('}')? + 1..],
+                None => &line[name_end..],
+            };
+            let value = rest.split_whitespace().next()?.parse::<f64>().ok()?;
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+/// Counters of the service's own deployment activity since it started
+#[derive(Debug, Default)]
+pub struct ServiceMetrics {
+    deployment_attempts: AtomicU64,
+    /// Failed deployments, keyed by the step they failed in
+    deployment_failures: Mutex<BTreeMap<String, u64>>,
+}
+
+impl ServiceMetrics {
+    /// Count a deployment that started
+    pub fn record_deployment_attempt(&self) {
+        self.deployment_attempts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a deployment that failed while running `step`, if a step was running
+    pub fn record_deployment_failure(&self, step: Option<DeploymentStep>) {
+        let phase = step.map_or("finishing".to_string(), |step| {
+            serde_json::to_value(step)
+                .ok()
+                .and_then(|value| value.as_str().map(str::to_string))
+                .unwrap_or_default()
+        });
+        let mut failures = self
+            .deployment_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *failures.entry(phase).or_default() += 1;
+    }
+
+    /// Render the counters and the state of `status` in Prometheus text format
+    ///
+    /// Every sample is labelled with the rollup's chain ID once the metadata is known. The
+    /// rollup's name and operator labels are carried by `orbit_raas_rollup_info`, to be
+    /// joined on `chain_id`.
+    pub fn render(&self, status: &DeploymentStatus) -> String {
+        let chain_id = status
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.chain_id.to_string());
+        let mut rollup = Vec::new();
+        if let Some(chain_id) = &chain_id {
+            rollup.push(("chain_id", chain_id.as_str()));
+        }
+
+        let mut out = String::new();
+        write_header(
+            &mut out,
+            "orbit_raas_deployment_attempts_total",
+            "counter",
+            "Deployments started since the service started",
+        );
+        write_sample(
+            &mut out,
+            "orbit_raas_deployment_attempts_total",
+            &rollup,
+            self.deployment_attempts.load(Ordering::Relaxed),
+        );
+
+        write_header(
+            &mut out,
+            "orbit_raas_deployment_failures_total",
+            "counter",
+            "Failed deployments since the service started, by the step they failed in",
+        );
+        let failures = self
+            .deployment_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        for (phase, count) in failures.iter() {
+            let mut labels = rollup.clone();
+            labels.push(("phase", phase));
+            write_sample(
+                &mut out,
+                "orbit_raas_deployment_failures_total",
+                &labels,
+                *count,
+            );
+        }
+
+        let gauges = [
+            (
+                "orbit_raas_deployed",
+                "Whether the rollup is deployed and running",
+                u64::from(status.deployed),
+            ),
+            (
+                "orbit_raas_healthy",
+                "Whether the rollup's RPC was serving requests when deployment finished",
+                u64::from(status.healthy),
+            ),
+            (
+                "orbit_raas_containers",
+                "Rollup containers tracked by the service",
+                status.container_ids.len() as u64,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            write_header(&mut out, name, "gauge", help);
+            write_sample(&mut out, name, &rollup, value);
+        }
+
+        if let Some(metadata) = &status.metadata {
+            write_header(
+                &mut out,
+                "orbit_raas_rollup_info",
+                "gauge",
+                "Rollup name and operator labels, each as label_<key>; always 1",
+            );
+            let label_names: Vec<String> = metadata
+                .labels
+                .keys()
+                .map(|key| format!("label_{}", metric_label_name(key)))
+                .collect();
+            let mut labels = rollup.clone();
+            labels.push(("name", metadata.name.as_str()));
+            for (name, value) in label_names.iter().zip(metadata.labels.values()) {
+                // Keys that only differ in characters a label name can't hold collide
+                if labels.iter().all(|(existing, _)| existing != name) {
+                    labels.push((name, value));
+                }
+            }
+            write_sample(&mut out, "orbit_raas_rollup_info", &labels, 1);
+        }
+        out
+    }
+}
+
+/// Turn an operator label key into a valid Prometheus label name
+fn metric_label_name(key: &str) -> String {
+    key.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Write the `HELP` and `TYPE` lines of a metric
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Write one sample, escaping label values as the text format requires
+fn write_sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: u64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| {
+                let value = value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n");
+                format!("{}=\"{}\"", key, value)
+            })
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ChainId, RollupMetadata, parse_labels};
+
+    #[test]
+    fn avail_stats_ignore_histogram_buckets() {
+        let stats = parse_avail_stats(
+            "# TYPE avail_submission_size_bytes histogram
+avail_submission_size_bytes_bucket{le=\"1024\"} 3
+avail_submission_size_bytes_bucket{le=\"+Inf\"} 5
+avail_submission_size_bytes_sum 4096
+avail_submission_size_bytes_count 5
+avail_submissions_total 5
+avail_submission_duration_seconds_bucket{le=\"1\"} 4
+avail_submission_duration_seconds_sum 2.5
+avail_submission_failures_total{reason=\"timeout\"} 1
+avail_submission_failures_total{reason=\"rejected\"} 2
+arb_sequencer_batches_total 9
+",
+        );
+
+        assert_eq!(stats.bytes_submitted, Some(4096.0));
+        assert_eq!(stats.submission_count, Some(5.0));
+        assert_eq!(stats.submission_failures, Some(3.0));
+        assert!(stats.metrics.keys().all(|name| !name.ends_with("_bucket")));
+        assert!(!stats.metrics.contains_key("arb_sequencer_batches_total"));
+    }
+
+    #[test]
+    fn render_exports_rollup_labels_as_info_metric() {
+        let status = DeploymentStatus {
+            metadata: Some(RollupMetadata {
+                name: "My \"Rollup\"".to_string(),
+                chain_id: ChainId::new(42).unwrap(),
+                labels: parse_labels("team=infra,cost-center=7,cost_center=8").unwrap(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let rendered = ServiceMetrics::default().render(&status);
+        let info = "orbit_raas_rollup_info{chain_id=\"42\",name=\"My \\\"Rollup\\\"\",\
+                    label_cost_center=\"7\",label_team=\"infra\"} 1\n";
+        assert!(rendered.contains(info), "{}", rendered);
+        assert!(rendered.contains("orbit_raas_deployed{chain_id=\"42\"} 0\n"));
+    }
+}
@@ -0,0 +1,237 @@
+//! Periodic persistence of the deployment status
+//!
+//! The status is mutated on every log line, so instead of writing on each change a
+//! background task flushes it at a jittered interval, and immediately when a flush is
+//! requested (e.g. on terminal state changes or shutdown). Unchanged snapshots are skipped.
+//!
+//! Where the status is written is up to a [`StatusStore`]. A JSON file is the default,
+//! and other backends such as Redis or a database can be plugged in by implementing it.
+
+use crate::types::DeploymentStatus;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex, Notify, watch};
+use tokio::task::JoinHandle;
+
+/// Future returned by [`StatusStore`] operations
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, String>> + Send + 'a>>;
+
+/// Backend the deployment status is persisted to
+pub trait StatusStore: Send + Sync {
+    /// Load the stored status, or `None` if nothing has been stored yet
+    fn load(&self) -> StoreFuture<'_, Option<DeploymentStatus>>;
+
+    /// Replace the stored status
+    fn save(&self, status: DeploymentStatus) -> StoreFuture<'_, ()>;
+
+    /// Watch the stored status, which changes on every save
+    ///
+    /// Backends shared between instances should also report saves made by other instances.
+    fn watch(&self) -> watch::Receiver<Option<DeploymentStatus>>;
+}
+
+/// Status store keeping the status in a JSON file
+pub struct FileStatusStore {
+    path: PathBuf,
+    updates: watch::Sender<Option<DeploymentStatus>>,
+}
+
+impl FileStatusStore {
+    /// Create a store writing to `path`
+    ///
+    /// Only saves made through this store are reported to watchers.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            updates: watch::Sender::new(None),
+        }
+    }
+}
+
+impl StatusStore for FileStatusStore {
+    fn load(&self) -> StoreFuture<'_, Option<DeploymentStatus>> {
+        Box::pin(async move { DeploymentStatus::load_from_path(&self.path) })
+    }
+
+    fn save(&self, status: DeploymentStatus) -> StoreFuture<'_, ()> {
+        Box::pin(async move {
+            status.save_to_path(&self.path)?;
+            self.updates.send_replace(Some(status));
+            Ok(())
+        })
+    }
+
+    fn watch(&self) -> watch::Receiver<Option<DeploymentStatus>> {
+        self.updates.subscribe()
+    }
+}
+
+/// Status store keeping the status in memory, for tests and ephemeral setups
+#[derive(Default)]
+pub struct MemoryStatusStore {
+    status: watch::Sender<Option<DeploymentStatus>>,
+}
+
+impl MemoryStatusStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StatusStore for MemoryStatusStore {
+    fn load(&self) -> StoreFuture<'_, Option<DeploymentStatus>> {
+        Box::pin(async move { Ok(self.status.borrow().clone()) })
+    }
+
+    fn save(&self, status: DeploymentStatus) -> StoreFuture<'_, ()> {
+        Box::pin(async move {
+            self.status.send_replace(Some(status));
+            Ok(())
+        })
+    }
+
+    fn watch(&self) -> watch::Receiver<Option<DeploymentStatus>> {
+        self.status.subscribe()
+    }
+}
+
+/// Writes the shared deployment status to a [`StatusStore`]
+#[derive(Clone)]
+pub struct StatusPersister {
+    status: Arc<Mutex<DeploymentStatus>>,
+    store: Arc<dyn StatusStore>,
+    flush_requested: Arc<Notify>,
+    last_written: Arc<Mutex<Option<String>>>,
+}
+
+impl StatusPersister {
+    /// Create a persister writing `status` to `store`
+    pub fn new(status: Arc<Mutex<DeploymentStatus>>, store: Arc<dyn StatusStore>) -> Self {
+        Self {
+            status,
+            store,
+            flush_requested: Arc::new(Notify::new()),
+            last_written: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Spawn the background task flushing the status roughly every `interval`
+    pub fn spawn(&self, interval: Duration) -> JoinHandle<()> {
+        let persister = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(jittered(interval)) => {}
+                    _ = persister.flush_requested.notified() => {}
+                }
+
+                if let Err(e) = persister.flush().await {
+                    tracing::warn!("Failed to persist deployment status: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Ask the background task to flush as soon as possible
+    pub fn request_flush(&self) {
+        self.flush_requested.notify_one();
+    }
+
+    /// Write the status now, unless it is unchanged since the last write
+    pub async fn flush(&self) -> Result<(), String> {
+        let status = self.status.lock().await.clone();
+        let snapshot = serde_json::to_string(&status)
+            .map_err(|e| format!("Failed to serialize deployment status: {}", e))?;
+
+        let mut last_written = self.last_written.lock().await;
+        if last_written.as_deref() == Some(snapshot.as_str()) {
+            return Ok(());
+        }
+
+        self.store.save(status).await?;
+        *last_written = Some(snapshot);
+        Ok(())
+    }
+}
+
+/// Add up to 20% jitter to `interval`, so multiple instances don't flush in lockstep
+fn jittered(interval: Duration) -> Duration {
+    let max_jitter_ms = (interval.as_millis() / 5) as u64;
+    if max_jitter_ms == 0 {
+        return interval;
+    }
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.subsec_nanos() as u64)
+        .unwrap_or_default();
+    interval + Duration::from_millis(nanos % max_jitter_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_with_log(line: &str) -> DeploymentStatus {
+        DeploymentStatus {
+            logs: vec![line.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn file_store_round_trips_the_status() {
+        let path = std::env::temp_dir().join(format!(
+            "avail-orbit-raas-status-round-trip-{}.json",
+            std::process::id()
+        ));
+        let store = FileStatusStore::new(path.clone());
+        assert!(store.load().await.unwrap().is_none());
+
+        store.save(status_with_log("saved")).await.unwrap();
+        let loaded = store.load().await;
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.unwrap().unwrap().logs, ["saved"]);
+        assert_eq!(store.watch().borrow().as_ref().unwrap().logs, ["saved"]);
+    }
+
+    #[tokio::test]
+    async fn file_store_rejects_a_corrupt_file() {
+        let path = std::env::temp_dir().join(format!(
+            "avail-orbit-raas-status-corrupt-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, "{ not json").unwrap();
+        let loaded = FileStatusStore::new(path.clone()).load().await;
+        let _ = std::fs::remove_file(&path);
+
+        let error = loaded.unwrap_err();
+        assert!(error.contains("Failed to parse"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn persister_skips_unchanged_status() {
+        let status = Arc::new(Mutex::new(status_with_log("first")));
+        let store = Arc::new(MemoryStatusStore::new());
+        let mut saves = store.watch();
+        let persister = StatusPersister::new(status.clone(), store.clone());
+
+        persister.flush().await.unwrap();
+        assert!(saves.has_changed().unwrap());
+        saves.mark_unchanged();
+
+        persister.flush().await.unwrap();
+        assert!(!saves.has_changed().unwrap());
+
+        status.lock().await.push_log("second");
+        persister.flush().await.unwrap();
+        assert!(saves.has_changed().unwrap());
+        let saved = store.load().await.unwrap().unwrap();
+        assert_eq!(saved.logs, ["first", "second"]);
+    }
+}
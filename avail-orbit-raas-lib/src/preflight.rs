@@ -0,0 +1,111 @@
+//! Host resource preflight checks for Avail Orbit RaaS
+//!
+//! Checks CPU count, available memory, and free disk in the working directory against the
+//! operator's minimums before deploying, so a deployment fails early with a clear message
+//! instead of dying midway on an undersized host.
+
+use crate::config::DeploymentConfig;
+use std::path::Path;
+use tokio::process::Command;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// Resources available on the host
+///
+/// Values that can't be determined on this platform are `None` and are not checked.
+#[derive(Clone, Debug, Default)]
+pub struct HostResources {
+    /// Number of CPUs available to this process
+    pub cpus: Option<usize>,
+    /// Memory available for new processes, in bytes
+    pub memory_available: Option<u64>,
+    /// Free disk space on the working directory's filesystem, in bytes
+    pub disk_available: Option<u64>,
+}
+
+impl HostResources {
+    /// Measure the resources available for a deployment into `working_dir`
+    pub async fn measure(working_dir: &Path) -> Self {
+        Self {
+            cpus: std::thread::available_parallelism()
+                .map(|cpus| cpus.get())
+                .ok(),
+            memory_available: memory_available(),
+            disk_available: disk_available(working_dir).await,
+        }
+    }
+
+    /// Check the resources against the configured minimums, listing every shortfall
+    pub fn check(&self, deployment: &DeploymentConfig) -> Result<(), String> {
+        let mut shortfalls = Vec::new();
+
+        if let Some(cpus) = self.cpus.filter(|cpus| *cpus < deployment.min_cpus) {
+            shortfalls.push(format!(
+                "insufficient CPUs: {} available, {} required",
+                cpus, deployment.min_cpus
+            ));
+        }
+        let min_memory = deployment.min_memory_gb * GIB;
+        if let Some(memory) = self.memory_available.filter(|memory| *memory < min_memory) {
+            shortfalls.push(format!(
+                "insufficient memory: {}GB available, {}GB required",
+                memory / GIB,
+                deployment.min_memory_gb
+            ));
+        }
+        let min_disk = deployment.min_disk_gb * GIB;
+        if let Some(disk) = self.disk_available.filter(|disk| *disk < min_disk) {
+            shortfalls.push(format!(
+                "insufficient disk: {}GB available, {}GB required",
+                disk / GIB,
+                deployment.min_disk_gb
+            ));
+        }
+
+        if shortfalls.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Host does not meet deployment requirements: {}",
+                shortfalls.join("; ")
+            ))
+        }
+    }
+}
+
+/// Available memory from `/proc/meminfo`, in bytes
+fn memory_available() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo
+        .lines()
+        .find(|line| line.starts_with("MemAvailable:"))?;
+    let kib = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kib * 1024)
+}
+
+/// Free disk space on the filesystem holding `path`, in bytes
+///
+/// The working directory may not exist yet, so its nearest existing ancestor is measured.
+async fn disk_available(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|dir| dir.exists())?;
+    let output = Command::new("df")
+        .arg("-Pk")
+        .arg(existing)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // POSIX format: Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let kib = stdout
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse::<u64>()
+        .ok()?;
+    Some(kib * 1024)
+}
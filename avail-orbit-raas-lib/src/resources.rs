@@ -0,0 +1,146 @@
+//! Container resource usage for Avail Orbit RaaS
+//!
+//! Samples CPU, memory, and disk usage of the rollup's containers with `docker stats` and
+//! `docker inspect`, so remote operators can spot resource pressure. Containers that can't
+//! be sampled are reported as errors alongside the usage of the others.
+
+use crate::OrbitContext;
+use crate::util::docker_error;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+/// Resource usage of a single container
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ContainerUsage {
+    /// Container ID
+    pub container_id: String,
+    /// Container name
+    pub name: String,
+    /// CPU usage, in percent of one core
+    pub cpu_percent: Option<f64>,
+    /// Memory in use, in bytes
+    pub memory_bytes: Option<u64>,
+    /// Memory limit, in bytes
+    pub memory_limit_bytes: Option<u64>,
+    /// Size of the container's writable layer, in bytes
+    pub disk_bytes: Option<u64>,
+}
+
+/// Resource usage across the rollup's containers
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// Usage of each container that could be sampled
+    pub containers: Vec<ContainerUsage>,
+    /// Total CPU usage, in percent of one core
+    pub total_cpu_percent: f64,
+    /// Total memory in use, in bytes
+    pub total_memory_bytes: u64,
+    /// Total size of the containers' writable layers, in bytes
+    pub total_disk_bytes: u64,
+    /// Containers that could not be sampled, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Sample the resource usage of the rollup's containers
+pub async fn collect_resource_usage(context: &OrbitContext) -> Result<ResourceUsage, String> {
+    let container_ids = {
+        let status = context.status.lock().await;
+        if !status.deployed {
+            return Err("Rollup not deployed".to_string());
+        }
+        status.container_ids.clone()
+    };
+
+    let mut usage = ResourceUsage::default();
+    for container_id in &container_ids {
+        match container_usage(container_id).await {
+            Ok(container) => {
+                usage.total_cpu_percent += container.cpu_percent.unwrap_or_default();
+                usage.total_memory_bytes += container.memory_bytes.unwrap_or_default();
+                usage.total_disk_bytes += container.disk_bytes.unwrap_or_default();
+                usage.containers.push(container);
+            }
+            Err(e) => usage.errors.push(format!("{}: {}", container_id, e)),
+        }
+    }
+
+    Ok(usage)
+}
+
+/// Sample one container's usage
+async fn container_usage(container_id: &str) -> Result<ContainerUsage, String> {
+    let output = Command::new("docker")
+        .args([
+            "stats",
+            "--no-stream",
+            "--format",
+            "{{json .}}",
+            container_id,
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run docker stats: {}", e))?;
+    if !output.status.success() {
+        return Err(docker_error(String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Invalid docker stats output: {}", e))?;
+    let (memory_bytes, memory_limit_bytes) = match stats["MemUsage"].as_str() {
+        Some(mem_usage) => match mem_usage.split_once('/') {
+            Some((used, limit)) => (parse_size(used), parse_size(limit)),
+            None => (parse_size(mem_usage), None),
+        },
+        None => (None, None),
+    };
+
+    Ok(ContainerUsage {
+        container_id: container_id.to_string(),
+        name: stats["Name"].as_str().unwrap_or_default().to_string(),
+        cpu_percent: stats["CPUPerc"].as_str().and_then(parse_percent),
+        memory_bytes,
+        memory_limit_bytes,
+        disk_bytes: writable_layer_size(container_id).await,
+    })
+}
+
+/// Size of a container's writable layer, as reported by `docker inspect --size`
+async fn writable_layer_size(container_id: &str) -> Option<u64> {
+    let output = Command::new("docker")
+        .args(["inspect", "--size", "--format", "{{.SizeRw}}", container_id])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Parse a percentage such as `12.34%`
+fn parse_percent(value: &str) -> Option<f64> {
+    value.trim().trim_end_matches('%').parse().ok()
+}
+
+/// Parse a size as printed by `docker stats`, such as `1.5GiB` or `512kB`, into bytes
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let multiplier: f64 = match unit {
+        "" | "B" => 1.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    Some((number * multiplier) as u64)
+}
@@ -0,0 +1,423 @@
+//! JSON-RPC helpers for Avail Orbit RaaS
+//!
+//! Minimal JSON-RPC client functions used to query the parent chain and probe the rollup's
+//! RPC endpoint for readiness. Callers pass a client built with
+//! [`ProxyConfig::http_client`](crate::config::ProxyConfig::http_client) so proxy settings apply.
+
+use crate::util::redact_url;
+use serde_json::{Value, json};
+use std::future::Future;
+use std::time::Duration;
+
+/// Time a single readiness probe may take, so a hanging socket doesn't use up every attempt
+const PROBE_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Send a JSON-RPC request and return its `result` field
+pub async fn json_rpc_request(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    method: &str,
+    params: Value,
+) -> Result<Value, String> {
+    json_rpc_call(client, rpc_url, method, params)
+        .await?
+        .map_err(|error| format!("{} returned an error: {}", method, error))
+}
+
+/// Send a JSON-RPC request and return its `result` field, or the `error` field if the
+/// node answered with one
+///
+/// The outer error is reserved for requests that got no valid answer, such as network
+/// failures, so callers can tell a node's refusal apart from an unreachable node.
+async fn json_rpc_call(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    method: &str,
+    params: Value,
+) -> Result<Result<Value, Value>, String> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+
+    let response = client
+        .post(rpc_url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to send {} request to {}: {}",
+                method,
+                redact_url(rpc_url),
+                e.without_url()
+            )
+        })?;
+
+    let body: Value = response.json().await.map_err(|e| {
+        format!(
+            "Invalid {} response from {}: {}",
+            method,
+            redact_url(rpc_url),
+            e.without_url()
+        )
+    })?;
+
+    if let Some(error) = body.get("error") {
+        return Ok(Err(error.clone()));
+    }
+
+    body.get("result")
+        .cloned()
+        .map(Ok)
+        .ok_or_else(|| format!("{} response is missing a result", method))
+}
+
+/// Parse a `0x`-prefixed hex quantity as returned by JSON-RPC
+pub fn parse_hex_quantity(value: &Value) -> Result<u64, String> {
+    let hex = value
+        .as_str()
+        .ok_or_else(|| format!("Expected a hex string, got {}", value))?;
+
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Invalid hex quantity '{}': {}", hex, e))
+}
+
+/// Query the chain ID of an EVM JSON-RPC endpoint
+pub async fn get_chain_id(client: &reqwest::Client, rpc_url: &str) -> Result<u64, String> {
+    let result = json_rpc_request(client, rpc_url, "eth_chainId", json!([])).await?;
+    parse_hex_quantity(&result)
+}
+
+/// Query the latest block number of an EVM JSON-RPC endpoint
+pub async fn get_block_number(client: &reqwest::Client, rpc_url: &str) -> Result<u64, String> {
+    let result = json_rpc_request(client, rpc_url, "eth_blockNumber", json!([])).await?;
+    parse_hex_quantity(&result)
+}
+
+/// Query the timestamp of a block, in seconds since the Unix epoch
+pub async fn get_block_timestamp(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    block: u64,
+) -> Result<u64, String> {
+    let result = json_rpc_request(
+        client,
+        rpc_url,
+        "eth_getBlockByNumber",
+        json!([format!("{:#x}", block), false]),
+    )
+    .await?;
+    parse_hex_quantity(&result["timestamp"])
+}
+
+/// Call a contract at the latest block and return the result as a `uint256` truncated to `u64`
+pub async fn call_uint(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    to: &str,
+    data: &str,
+) -> Result<u64, String> {
+    let result = json_rpc_request(
+        client,
+        rpc_url,
+        "eth_call",
+        json!([{ "to": to, "data": data }, "latest"]),
+    )
+    .await?;
+
+    let hex = result
+        .as_str()
+        .ok_or_else(|| format!("Expected hex return data, got {}", result))?;
+    let digits = hex.trim_start_matches("0x");
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid return data '{}'", hex));
+    }
+    let low = &digits[digits.len().saturating_sub(16)..];
+    if low.is_empty() {
+        return Err(format!("Call to {} returned no data", to));
+    }
+    u64::from_str_radix(low, 16).map_err(|e| format!("Invalid return data '{}': {}", hex, e))
+}
+
+/// Query the number of the sequencer batch holding an Arbitrum chain block
+///
+/// Uses Nitro's `arb_findBatchContainingBlock`, which answers with an error for blocks not
+/// yet in a batch posted to the parent chain; those return `None`. Requests that get no
+/// answer at all are errors.
+pub async fn find_batch_containing_block(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    block: u64,
+) -> Result<Option<u64>, String> {
+    match json_rpc_call(
+        client,
+        rpc_url,
+        "arb_findBatchContainingBlock",
+        json!([format!("{:#x}", block)]),
+    )
+    .await?
+    {
+        Ok(result) => parse_hex_quantity(&result).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Check whether an account holds a non-zero balance
+pub async fn has_balance(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    address: &str,
+) -> Result<bool, String> {
+    let result = json_rpc_request(
+        client,
+        rpc_url,
+        "eth_getBalance",
+        json!([address, "latest"]),
+    )
+    .await?;
+
+    let balance = result
+        .as_str()
+        .ok_or_else(|| format!("Expected hex balance, got {}", result))?;
+    Ok(!balance
+        .trim_start_matches("0x")
+        .trim_start_matches('0')
+        .is_empty())
+}
+
+/// Check whether contract code is deployed at an address
+pub async fn has_code(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    address: &str,
+) -> Result<bool, String> {
+    let result =
+        json_rpc_request(client, rpc_url, "eth_getCode", json!([address, "latest"])).await?;
+
+    let code = result
+        .as_str()
+        .ok_or_else(|| format!("Expected hex code, got {}", result))?;
+    Ok(!code.trim_start_matches("0x").is_empty())
+}
+
+/// Poll an RPC endpoint with `eth_chainId` until it responds
+///
+/// Makes up to `max_attempts` attempts, each limited to a few seconds, waiting `interval`
+/// between them, and returns the chain ID reported by the first successful response.
+pub async fn probe_rpc_ready(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    max_attempts: u32,
+    interval: Duration,
+) -> Result<u64, String> {
+    let attempts = max_attempts.max(1);
+    let mut last_error = String::new();
+
+    for attempt in 1..=attempts {
+        let probe = tokio::time::timeout(PROBE_REQUEST_TIMEOUT, get_chain_id(client, rpc_url))
+            .await
+            .unwrap_or_else(|_| Err(format!("No response within {:?}", PROBE_REQUEST_TIMEOUT)));
+        match probe {
+            Ok(chain_id) => return Ok(chain_id),
+            Err(e) => {
+                tracing::info!(
+                    "RPC {} not ready (attempt {}/{}): {}",
+                    redact_url(rpc_url),
+                    attempt,
+                    attempts,
+                    e
+                );
+                last_error = e;
+            }
+        }
+
+        if attempt < attempts {
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    Err(format!(
+        "RPC {} not ready after {} attempts: {}",
+        redact_url(rpc_url),
+        attempts,
+        last_error
+    ))
+}
+
+/// Run `request` against each RPC URL in order until one succeeds
+///
+/// Returns the first successful result, or every URL's error if all of them fail.
+pub async fn with_failover<'a, T, F, Fut>(
+    rpc_urls: &'a [String],
+    mut request: F,
+) -> Result<T, String>
+where
+    F: FnMut(&'a str) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let mut errors = Vec::new();
+    for rpc_url in rpc_urls {
+        match request(rpc_url).await {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                tracing::warn!(
+                    "RPC {} failed, trying the next one: {}",
+                    redact_url(rpc_url),
+                    e
+                );
+                errors.push(e);
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        return Err("No RPC URLs configured".to_string());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answer every JSON-RPC request on a local port with `response`, returning the URL and
+    /// the number of requests served so far
+    fn serve_json_rpc(response: Value) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let served = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(&mut stream);
+                let mut content_length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or(0) > 0 && !line.trim().is_empty() {
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap_or(0);
+                        }
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+
+                served.fetch_add(1, Ordering::SeqCst);
+                let body = response.to_string();
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        (url, requests)
+    }
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::builder().no_proxy().build().unwrap()
+    }
+
+    #[test]
+    fn parse_hex_quantity_reads_prefixed_hex() {
+        assert_eq!(parse_hex_quantity(&json!("0x1a")).unwrap(), 26);
+        assert_eq!(parse_hex_quantity(&json!("0x0")).unwrap(), 0);
+        assert!(parse_hex_quantity(&json!(26)).is_err());
+        assert!(parse_hex_quantity(&json!("0xzz")).is_err());
+        assert!(parse_hex_quantity(&json!("0x")).is_err());
+    }
+
+    #[tokio::test]
+    async fn call_uint_reads_the_low_64_bits() {
+        let word = format!("0x{:0>64}", "1000000000000002a");
+        let (url, _) = serve_json_rpc(json!({ "jsonrpc": "2.0", "id": 1, "result": word }));
+
+        assert_eq!(call_uint(&client(), &url, "0x01", "0x").await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn call_uint_rejects_empty_and_non_hex_data() {
+        let (url, _) = serve_json_rpc(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x" }));
+        let error = call_uint(&client(), &url, "0x01", "0x").await.unwrap_err();
+        assert!(error.contains("no data"), "{}", error);
+
+        // Multi-byte characters must not be sliced through
+        let (url, _) = serve_json_rpc(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x€€€€€€" }));
+        let error = call_uint(&client(), &url, "0x01", "0x").await.unwrap_err();
+        assert!(error.contains("Invalid return data"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn with_failover_tries_urls_in_order_until_one_succeeds() {
+        let urls = ["a", "b", "c"].map(String::from);
+        let mut tried = Vec::new();
+        let result = with_failover(&urls, |url| {
+            tried.push(url);
+            async move {
+                match url {
+                    "a" => Err("a is down".to_string()),
+                    _ => Ok(url.to_string()),
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "b");
+        assert_eq!(tried, ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn with_failover_reports_every_error() {
+        let urls = ["a", "b"].map(String::from);
+        let result: Result<(), String> =
+            with_failover(&urls, |url| async move { Err(format!("{} is down", url)) }).await;
+        assert_eq!(result.unwrap_err(), "a is down; b is down");
+
+        let result: Result<(), String> = with_failover(&[], |_| async { Ok(()) }).await;
+        assert_eq!(result.unwrap_err(), "No RPC URLs configured");
+    }
+
+    #[tokio::test]
+    async fn probe_rpc_ready_makes_the_requested_attempts() {
+        let (url, requests) = serve_json_rpc(
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32000, "message": "starting" } }),
+        );
+
+        let error = probe_rpc_ready(&client(), &url, 3, Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(error.contains("after 3 attempts"), "{}", error);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Zero attempts still probes once
+        let error = probe_rpc_ready(&client(), &url, 0, Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(error.contains("after 1 attempts"), "{}", error);
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn probe_rpc_ready_returns_the_chain_id() {
+        let (url, requests) =
+            serve_json_rpc(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x66eee" }));
+
+        assert_eq!(
+            probe_rpc_ready(&client(), &url, 5, Duration::ZERO)
+                .await
+                .unwrap(),
+            421614
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Control plane self-test for Avail Orbit RaaS
+//!
+//! Checks that a newly provisioned controller can deploy without deploying anything: the
+//! required tools are installed, Docker and Tangle are reachable, the working directory is
+//! writable, and the configuration is valid. Every check runs and reports on its own, so
+//! one report lists everything that needs fixing.
+
+use crate::OrbitContext;
+use crate::config::{AvailOrbitConfig, DeploymentConfig, ExecutionMode};
+use crate::rpc::{get_chain_id, json_rpc_request};
+use crate::startup::{StartupReport, deployment_errors};
+use crate::util::{
+    check_cast_available, check_docker_available, check_docker_compose_available,
+    check_docker_daemon_access, check_package_manager_available, create_private_dir,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::future::Future;
+
+/// Outcome of one self-test check
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    /// Check name
+    pub name: String,
+    /// Whether the check passed
+    pub passed: bool,
+    /// What was found, or why the check failed
+    pub detail: String,
+}
+
+/// Outcome of a self-test
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    /// Whether every check passed
+    pub passed: bool,
+    /// Outcome of each check, in the order they ran
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Record the outcome of a check
+    fn record(&mut self, name: &str, result: Result<String, String>) {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        self.checks.push(SelfTestCheck {
+            name: name.to_string(),
+            passed,
+            detail,
+        });
+    }
+}
+
+/// Check that the controller is healthy and correctly configured, without deploying
+pub async fn run_self_test(context: &OrbitContext) -> SelfTestReport {
+    let operator_config = context.operator_config.lock().await.clone();
+    let metadata = context.status.lock().await.metadata.clone();
+    let deployment = &operator_config.deployment;
+    let mut report = SelfTestReport::default();
+
+    report.record(
+        "docker",
+        tool_check("Docker", check_docker_available()).await,
+    );
+    report.record(
+        "docker_compose",
+        tool_check("Docker Compose", check_docker_compose_available()).await,
+    );
+    report.record(
+        "docker_daemon",
+        check_docker_daemon_access()
+            .await
+            .map(|()| "Docker daemon is reachable".to_string()),
+    );
+    report.record("cast", tool_check("cast", check_cast_available()).await);
+    let package_manager = deployment.package_manager;
+    report.record(
+        "package_manager",
+        if deployment.execution_mode == ExecutionMode::Host {
+            tool_check(
+                &package_manager.to_string(),
+                check_package_manager_available(package_manager),
+            )
+            .await
+        } else {
+            Ok(format!(
+                "{} runs in the toolchain container",
+                package_manager
+            ))
+        },
+    );
+
+    report.record("tangle", check_tangle(deployment).await);
+    report.record("working_dir", check_working_dir(context).await);
+
+    let config = match metadata {
+        // The same checks that gate deploying at startup
+        Some(metadata) => AvailOrbitConfig::new(operator_config.clone(), metadata)
+            .and_then(|config| StartupReport::new(&config).check().map(|()| Some(config))),
+        None => {
+            let mut errors = deployment_errors(deployment);
+            errors.extend(
+                [
+                    operator_config.validate_keys(),
+                    deployment.validate_parent_chain_fallbacks(),
+                    deployment.validate_local_fork(),
+                ]
+                .into_iter()
+                .filter_map(Result::err),
+            );
+            if errors.is_empty() {
+                Ok(None)
+            } else {
+                Err(errors.join("; "))
+            }
+        }
+    };
+    match config {
+        Ok(Some(config)) => {
+            report.record(
+                "config",
+                Ok("Operator config and metadata are valid".to_string()),
+            );
+            report.record("parent_chain", check_parent_chain(&config).await);
+        }
+        Ok(None) => report.record(
+            "config",
+            Ok("Operator config is valid; no rollup metadata to check yet".to_string()),
+        ),
+        Err(e) => report.record("config", Err(e)),
+    }
+
+    report.passed = report.checks.iter().all(|check| check.passed);
+    report
+}
+
+/// Turn a tool availability check into a check outcome
+async fn tool_check(
+    tool: &str,
+    check: impl Future<Output = Result<bool, String>>,
+) -> Result<String, String> {
+    match check.await {
+        Ok(true) => Ok(format!("{} is available", tool)),
+        Ok(false) => Err(format!(
+            "{} is installed but not responding correctly",
+            tool
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+/// Check that the Tangle node's RPC responds
+///
+/// The service sets the URL to the runner's endpoint when it starts, so this probes the
+/// node jobs actually arrive from.
+async fn check_tangle(deployment: &DeploymentConfig) -> Result<String, String> {
+    let client = deployment.proxy.http_client()?;
+    let rpc_url = &deployment.tangle_rpc_url;
+    let health = json_rpc_request(&client, rpc_url, "system_health", json!([])).await?;
+    Ok(format!(
+        "Tangle node at {} has {} peers",
+        rpc_url,
+        health["peers"].as_u64().unwrap_or_default()
+    ))
+}
+
+/// Check that files can be created in the working directory
+async fn check_working_dir(context: &OrbitContext) -> Result<String, String> {
+    let working_dir = context.working_dir().await;
+    create_private_dir(&working_dir)
+        .map_err(|e| format!("{} is not writable: {}", working_dir.display(), e))?;
+    Ok(format!("{} is writable", working_dir.display()))
+}
+
+/// Check that the parent chain RPC serves a chain of the configured settlement layer
+async fn check_parent_chain(config: &AvailOrbitConfig) -> Result<String, String> {
+    let deployment = config.get_deployment_config();
+    let client = deployment.proxy.http_client()?;
+    let chain_id = get_chain_id(&client, config.get_parent_chain_rpc()).await?;
+    deployment
+        .settlement_layer
+        .validate_parent_chain_id(chain_id)?;
+    Ok(format!(
+        "Parent chain RPC serves chain ID {} ({} settlement)",
+        chain_id, deployment.settlement_layer
+    ))
+}
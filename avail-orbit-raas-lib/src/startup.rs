@@ -0,0 +1,187 @@
+//! Startup validation report for Avail Orbit RaaS
+//!
+//! Summarizes what the service is about to deploy in one place, with secrets and RPC
+//! credentials redacted, and runs the checks that must pass before deploying.
+
+use crate::config::{AvailOrbitConfig, DeploymentConfig};
+use crate::types::ChainId;
+use crate::util::redact_url;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Summary of the configuration the service starts with
+#[derive(Clone, Debug, Serialize)]
+pub struct StartupReport {
+    /// Rollup name
+    pub name: String,
+    /// Rollup chain ID
+    pub chain_id: ChainId,
+    /// Parent chain RPC with any credentials in its path or query redacted
+    pub parent_chain_rpc: String,
+    /// Settlement layer of the parent chain
+    pub settlement_layer: String,
+    /// Avail node the rollup posts data to, redacted like the parent chain RPC
+    pub avail_api_url: String,
+    /// Avail app ID
+    pub avail_app_id: u32,
+    /// Nitro node image
+    pub node_image: String,
+    /// Deployment profile
+    pub profile: String,
+    /// Where the deploy scripts run
+    pub execution_mode: String,
+    /// Package manager running the deploy scripts
+    pub package_manager: String,
+    /// Object prefix the S3 fallback writes rollup state under, if it is enabled
+    pub s3_object_prefix: Option<String>,
+    /// Optional features and whether each is enabled
+    pub features: BTreeMap<&'static str, bool>,
+    /// Problems that don't prevent deploying
+    pub warnings: Vec<String>,
+    /// Problems that prevent deploying
+    pub errors: Vec<String>,
+}
+
+impl StartupReport {
+    /// Build the report for `config`, running every startup check
+    pub fn new(config: &AvailOrbitConfig) -> Self {
+        let metadata = config.get_metadata();
+        let operator_config = config.operator_config();
+        let deployment = config.get_deployment_config();
+
+        let features = BTreeMap::from([
+            ("s3_fallback", metadata.fallback_s3_enable),
+            ("read_replica", deployment.read_replica),
+            ("artifact_cache", deployment.artifact_cache_dir.is_some()),
+            ("sparse_checkout", deployment.git_sparse_checkout),
+            ("resume_interrupted", deployment.resume_interrupted),
+            ("force_redeploy", deployment.force_redeploy),
+            ("launch_anvil", deployment.launch_anvil),
+        ]);
+
+        let mut warnings = deployment.exposure_warnings();
+        if !deployment.extra_env.is_empty() {
+            warnings.push(format!(
+                "Passing extra environment to deploy scripts: {}",
+                deployment.extra_env_summary()
+            ));
+        }
+
+        let mut errors = deployment_errors(deployment);
+        if metadata.fallback_s3_enable {
+            let required = [
+                (
+                    "FALLBACKS3_ACCESS_KEY",
+                    &operator_config.fallback_s3_access_key,
+                ),
+                (
+                    "FALLBACKS3_SECRET_KEY",
+                    &operator_config.fallback_s3_secret_key,
+                ),
+                ("FALLBACKS3_REGION", &operator_config.fallback_s3_region),
+                ("FALLBACKS3_BUCKET", &operator_config.fallback_s3_bucket),
+            ];
+            for (name, value) in required {
+                if value.as_deref().is_none_or(str::is_empty) {
+                    errors.push(format!("S3 fallback is enabled but {} is not set", name));
+                }
+            }
+        }
+
+        Self {
+            name: metadata.name.clone(),
+            chain_id: metadata.chain_id,
+            parent_chain_rpc: redact_url(&metadata.parent_chain_rpc),
+            settlement_layer: deployment.settlement_layer.to_string(),
+            avail_api_url: redact_url(&deployment.avail_api_url),
+            avail_app_id: config.get_avail_app_id().get(),
+            node_image: deployment.node_image.clone(),
+            profile: deployment.profile.to_string(),
+            execution_mode: deployment.execution_mode.to_string(),
+            package_manager: deployment.package_manager.to_string(),
+            s3_object_prefix: metadata
+                .fallback_s3_enable
+                .then(|| config.fallback_s3_object_prefix()),
+            features,
+            warnings,
+            errors,
+        }
+    }
+
+    /// Fail with every error found if any check failed
+    pub fn check(&self) -> Result<(), String> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Startup validation failed: {}",
+                self.errors.join("; ")
+            ))
+        }
+    }
+}
+
+/// Run the startup checks that only depend on the deployment settings
+pub fn deployment_errors(deployment: &DeploymentConfig) -> Vec<String> {
+    [
+        deployment.validate_exposure(),
+        deployment.validate_extra_env(),
+        deployment.validate_package_manager(),
+        deployment.validate_working_dir(),
+        deployment.ports.validate(),
+    ]
+    .into_iter()
+    .filter_map(Result::err)
+    .collect()
+}
+
+impl std::fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let enabled: Vec<&str> = self
+            .features
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(name, _)| *name)
+            .collect();
+
+        writeln!(
+            f,
+            "Rollup:          {} (chain ID {})",
+            self.name, self.chain_id
+        )?;
+        writeln!(
+            f,
+            "Parent chain:    {} ({})",
+            self.parent_chain_rpc, self.settlement_layer
+        )?;
+        writeln!(
+            f,
+            "Avail:           {} (app ID {})",
+            self.avail_api_url, self.avail_app_id
+        )?;
+        writeln!(f, "Node image:      {}", self.node_image)?;
+        writeln!(f, "Profile:         {}", self.profile)?;
+        writeln!(
+            f,
+            "Deploy scripts:  {} on {}",
+            self.package_manager, self.execution_mode
+        )?;
+        match &self.s3_object_prefix {
+            Some(prefix) => writeln!(f, "S3 fallback:     {}", prefix)?,
+            None => writeln!(f, "S3 fallback:     disabled")?,
+        }
+        write!(f, "Features:        ")?;
+        if enabled.is_empty() {
+            writeln!(f, "none")?;
+        } else {
+            writeln!(f, "{}", enabled.join(", "))?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "WARNING: {}", warning)?;
+        }
+        for error in &self.errors {
+            writeln!(f, "ERROR: {}", error)?;
+        }
+        Ok(())
+    }
+}
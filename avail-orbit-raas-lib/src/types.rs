@@ -1,18 +1,478 @@
 //! Type definitions for Avail Orbit RaaS
 
+use crate::util::{is_address, redact_url, validate_url, write_private_file};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Most log lines kept in [`DeploymentStatus::logs`]; older lines are dropped first
+pub const MAX_STATUS_LOG_LINES: usize = 2000;
+
+/// Longest single log line kept in [`DeploymentStatus::logs`], in bytes
+pub const MAX_STATUS_LOG_LINE_BYTES: usize = 2048;
 
 /// Deployment status for the rollup
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct DeploymentStatus {
     /// Is the rollup deployed
     pub deployed: bool,
-    /// Deployment logs
+    /// Whether the rollup's RPC was serving requests when deployment finished
+    #[serde(default)]
+    pub healthy: bool,
+    /// Deployment logs, keeping only the most recent [`MAX_STATUS_LOG_LINES`]
     pub logs: Vec<String>,
     /// Public rollup metadata
     pub metadata: Option<RollupMetadata>,
     /// Docker container IDs
     pub container_ids: Vec<String>,
+    /// Container ID of the read replica, which is also listed in `container_ids`
+    #[serde(default)]
+    pub read_replica_container_id: Option<String>,
+    /// Contract addresses extracted from the deploy and bridge setup output
+    #[serde(default)]
+    pub contract_addresses: DeployedAddresses,
+    /// Deployment steps completed so far, in order
+    #[serde(default)]
+    pub completed_steps: Vec<DeploymentStep>,
+    /// Why the last deployment failed, if it did
+    #[serde(default)]
+    pub failure: Option<String>,
+    /// Size-capped script output captured for each deployment step, for debugging
+    #[serde(default)]
+    pub step_outputs: BTreeMap<DeploymentStep, String>,
+    /// Minimum L2 base fee in wei last set through the ArbOwner precompile
+    #[serde(default)]
+    pub min_l2_base_fee: Option<u64>,
+    /// L2 speed limit in gas per second last set through the ArbOwner precompile
+    #[serde(default)]
+    pub speed_limit: Option<u64>,
+    /// L2 block gas limit last set through the ArbOwner precompile
+    #[serde(default)]
+    pub block_gas_limit: Option<u64>,
+    /// Step that was running when a previous process stopped mid-deployment
+    #[serde(default)]
+    pub interrupted: Option<DeploymentStep>,
+    /// Phase the deployment is in, for showing progress without parsing the logs
+    #[serde(default)]
+    pub phase: DeploymentPhase,
+}
+
+impl DeploymentStatus {
+    /// Append a log line, dropping the oldest lines once the log is full
+    ///
+    /// Lines longer than [`MAX_STATUS_LOG_LINE_BYTES`] are cut short, so the log stays
+    /// bounded however much a script prints.
+    pub fn push_log(&mut self, line: impl Into<String>) {
+        let mut line = line.into();
+        if line.len() > MAX_STATUS_LOG_LINE_BYTES {
+            let mut end = MAX_STATUS_LOG_LINE_BYTES;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            line.truncate(end);
+            line.push_str(" [truncated]");
+        }
+        self.logs.push(line);
+        if self.logs.len() > MAX_STATUS_LOG_LINES {
+            let excess = self.logs.len() - MAX_STATUS_LOG_LINES;
+            self.logs.drain(..excess);
+        }
+    }
+
+    /// Write the status to `path` as JSON
+    pub fn save_to_path(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize deployment status: {}", e))?;
+        Self::write_snapshot(path, &json)
+    }
+
+    /// Read a status previously written with [`save_to_path`](Self::save_to_path)
+    ///
+    /// Returns `None` if there is no file at `path`.
+    pub fn load_from_path(path: &Path) -> Result<Option<Self>, String> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    /// Whether a deployment was started and has neither finished nor failed
+    ///
+    /// A rollup stopped on request finished deploying, so it isn't in progress.
+    pub fn is_in_progress(&self) -> bool {
+        self.metadata.is_some()
+            && !self.deployed
+            && self.failure.is_none()
+            && self.phase != DeploymentPhase::Stopped
+    }
+
+    /// Step a deployment in progress is running, i.e. the first one not yet completed
+    pub fn current_step(&self) -> Option<DeploymentStep> {
+        if !self.is_in_progress() {
+            return None;
+        }
+        DeploymentStep::all()
+            .iter()
+            .find(|step| !self.completed_steps.contains(step))
+            .copied()
+    }
+
+    /// Record that the deployment failed, and in which step
+    pub fn fail(&mut self, reason: String) {
+        self.phase = DeploymentPhase::Failed {
+            step: self.current_step(),
+            reason: reason.clone(),
+        };
+        self.failure = Some(reason);
+    }
+
+    /// Record that the rollup's containers were stopped on request
+    ///
+    /// The containers and metadata are kept, so the rollup can be restarted later.
+    pub fn mark_stopped(&mut self) {
+        self.deployed = false;
+        self.healthy = false;
+        self.phase = DeploymentPhase::Stopped;
+        self.push_log("Rollup stopped; restart it to bring it back up");
+    }
+
+    /// Mark a deployment in progress as interrupted, e.g. by a crash of the previous process
+    ///
+    /// Returns whether the status was in progress.
+    pub fn mark_interrupted(&mut self) -> bool {
+        if !self.is_in_progress() {
+            return false;
+        }
+        let step = self.current_step();
+        let during = step.map_or("finishing".to_string(), |step| step.to_string());
+        self.interrupted = step;
+        self.fail(format!("Deployment interrupted during: {}", during));
+        self.push_log(format!(
+            "Previous deployment was interrupted during: {}",
+            during
+        ));
+        true
+    }
+
+    /// Atomically replace the file at `path` with an already serialized status
+    pub(crate) fn write_snapshot(path: &Path, json: &str) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let tmp_path = path.with_extension("json.tmp");
+        write_private_file(&tmp_path, json)
+            .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+        std::fs::rename(&tmp_path, path)
+            .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+    }
+}
+
+/// A step of the rollup deployment pipeline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentStep {
+    /// Verify the parent chain matches the settlement layer
+    VerifyParentChain,
+    /// Pull the Avail Nitro node Docker image
+    PullDockerImage,
+    /// Clone the orbit SDK and setup script repositories
+    CloneRepositories,
+    /// Write the deployment `.env` file
+    CreateConfigFiles,
+    /// Deploy the rollup contracts to the parent chain
+    DeployContracts,
+    /// Start the chain with docker compose
+    StartChain,
+    /// Deploy the token bridge
+    DeployTokenBridge,
+}
+
+impl DeploymentStep {
+    /// All deployment steps in execution order
+    pub const fn all() -> &'static [DeploymentStep] {
+        &[
+            DeploymentStep::VerifyParentChain,
+            DeploymentStep::PullDockerImage,
+            DeploymentStep::CloneRepositories,
+            DeploymentStep::CreateConfigFiles,
+            DeploymentStep::DeployContracts,
+            DeploymentStep::StartChain,
+            DeploymentStep::DeployTokenBridge,
+        ]
+    }
+
+    /// Human-readable description of the step
+    pub fn description(&self) -> &'static str {
+        match self {
+            DeploymentStep::VerifyParentChain => "Verify parent chain",
+            DeploymentStep::PullDockerImage => "Pull Docker image",
+            DeploymentStep::CloneRepositories => "Clone repositories",
+            DeploymentStep::CreateConfigFiles => "Create configuration files",
+            DeploymentStep::DeployContracts => "Deploy rollup contracts",
+            DeploymentStep::StartChain => "Set up and start the chain",
+            DeploymentStep::DeployTokenBridge => "Deploy token bridge",
+        }
+    }
+}
+
+/// Coarse phase of a deployment
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeploymentPhase {
+    /// No deployment has started
+    #[default]
+    NotStarted,
+    /// Checking the parent chain
+    VerifyingParentChain,
+    /// Pulling the node image
+    PullingImage,
+    /// Cloning the orbit SDK and setup script
+    CloningRepos,
+    /// Writing the deployment configuration
+    CreatingConfigFiles,
+    /// Deploying the rollup contracts
+    DeployingContracts,
+    /// Starting the chain's containers
+    StartingChain,
+    /// Deploying the token bridge
+    DeployingBridge,
+    /// Every step finished and the rollup is running
+    Complete,
+    /// The rollup was deployed and then stopped on request; its containers are kept
+    Stopped,
+    /// The deployment failed while running `step`, if a step was running
+    Failed {
+        step: Option<DeploymentStep>,
+        reason: String,
+    },
+}
+
+impl From<DeploymentStep> for DeploymentPhase {
+    fn from(step: DeploymentStep) -> Self {
+        match step {
+            DeploymentStep::VerifyParentChain => DeploymentPhase::VerifyingParentChain,
+            DeploymentStep::PullDockerImage => DeploymentPhase::PullingImage,
+            DeploymentStep::CloneRepositories => DeploymentPhase::CloningRepos,
+            DeploymentStep::CreateConfigFiles => DeploymentPhase::CreatingConfigFiles,
+            DeploymentStep::DeployContracts => DeploymentPhase::DeployingContracts,
+            DeploymentStep::StartChain => DeploymentPhase::StartingChain,
+            DeploymentStep::DeployTokenBridge => DeploymentPhase::DeployingBridge,
+        }
+    }
+}
+
+impl fmt::Display for DeploymentStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Deployed contract addresses, keyed by contract name
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DeployedAddresses(BTreeMap<String, String>);
+
+impl DeployedAddresses {
+    /// Record the address of a contract, replacing any previous value
+    pub fn insert(&mut self, name: impl Into<String>, address: impl Into<String>) {
+        self.0.insert(name.into(), address.into());
+    }
+
+    /// Get the address of a contract by name
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Merge another set of addresses into this one, overwriting duplicates
+    pub fn extend(&mut self, other: DeployedAddresses) {
+        self.0.extend(other.0);
+    }
+
+    /// Iterate over `(name, address)` pairs in name order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Whether no addresses have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Outcome of restarting the sequencer container in place
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ContainerRestart {
+    /// ID of the restarted container
+    pub container_id: String,
+    /// Container state after the restart, e.g. `running`
+    pub state: String,
+    /// Health status after the restart, if the image defines a healthcheck
+    pub health: Option<String>,
+}
+
+/// Outcome of tearing down the rollup's compose stack
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Teardown {
+    /// Whether `docker compose down` finished within its timeout
+    pub compose_down_completed: bool,
+    /// Containers left after the compose down that had to be force-removed
+    pub force_removed: Vec<String>,
+    /// Containers that could not be removed, with the reason
+    pub errors: Vec<String>,
+}
+
+/// How far the batches posted to the parent chain trail the rollup's latest block
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct BatchLag {
+    /// Latest block of the rollup
+    pub latest_block: u64,
+    /// Number of batches the sequencer inbox on the parent chain has received
+    pub batch_count: u64,
+    /// Latest rollup block included in a posted batch, if any
+    pub last_batched_block: Option<u64>,
+    /// Blocks not yet posted to the parent chain
+    pub lag_blocks: u64,
+    /// Age in seconds of the oldest block not yet posted to the parent chain
+    pub lag_secs: u64,
+    /// Lag in seconds above which the rollup is flagged as lagging
+    pub threshold_secs: u64,
+    /// Whether the lag exceeds the threshold
+    pub lagging: bool,
+}
+
+/// Chain info of a rollup, as embedded in the node config's `chain.info-json`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ChainInfo {
+    /// Chain ID of the rollup
+    pub chain_id: u64,
+    /// Chain ID of the parent chain
+    pub parent_chain_id: u64,
+    /// Whether the parent chain is itself an Arbitrum chain
+    #[serde(default)]
+    pub parent_chain_is_arbitrum: bool,
+    /// Human-readable chain name
+    #[serde(default)]
+    pub chain_name: String,
+    /// Genesis chain config, including the `arbitrum` parameters
+    pub chain_config: serde_json::Value,
+    /// Rollup contracts on the parent chain
+    pub rollup: ChainRollupContracts,
+}
+
+/// Rollup contracts listed in the chain info
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ChainRollupContracts {
+    pub bridge: String,
+    pub inbox: String,
+    pub sequencer_inbox: String,
+    pub rollup: String,
+    /// Parent chain block the rollup was deployed at
+    #[serde(default)]
+    pub deployed_at: u64,
+}
+
+impl ChainInfo {
+    /// Parse and validate the chain info from a node config
+    ///
+    /// `chain.info-json` is a JSON array encoded as a string; the first entry is used.
+    pub fn from_node_config(node_config: &serde_json::Value) -> Result<Self, String> {
+        let info_json = node_config["chain"]["info-json"]
+            .as_str()
+            .ok_or("Node config has no chain.info-json")?;
+        let entries: Vec<ChainInfo> = serde_json::from_str(info_json)
+            .map_err(|e| format!("Malformed chain.info-json: {}", e))?;
+        let info = entries
+            .into_iter()
+            .next()
+            .ok_or("chain.info-json lists no chains")?;
+        info.validate()?;
+        Ok(info)
+    }
+
+    /// Check that the genesis config agrees with the chain ID and the contracts are addresses
+    pub fn validate(&self) -> Result<(), String> {
+        match self.chain_config["chainId"].as_u64() {
+            Some(chain_id) if chain_id == self.chain_id => {}
+            Some(chain_id) => {
+                return Err(format!(
+                    "Chain config chainId {} does not match chain-id {}",
+                    chain_id, self.chain_id
+                ));
+            }
+            None => return Err("Chain config is missing chainId".to_string()),
+        }
+        if !self.chain_config["arbitrum"].is_object() {
+            return Err("Chain config is missing its arbitrum parameters".to_string());
+        }
+
+        for (name, address) in [
+            ("bridge", &self.rollup.bridge),
+            ("inbox", &self.rollup.inbox),
+            ("sequencer-inbox", &self.rollup.sequencer_inbox),
+            ("rollup", &self.rollup.rollup),
+        ] {
+            if !is_address(address) {
+                return Err(format!(
+                    "Invalid {} address in chain info: {}",
+                    name, address
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Structured result of a state-changing job, returned as JSON
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct JobSummary {
+    /// Whether the job succeeded
+    pub success: bool,
+    /// Human-readable outcome
+    pub message: String,
+    /// Time the job took, in milliseconds
+    pub duration_ms: u64,
+    /// Containers affected by the job
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub containers: Vec<String>,
+    /// Contract addresses added or updated by the job
+    #[serde(default, skip_serializing_if = "DeployedAddresses::is_empty")]
+    pub addresses: DeployedAddresses,
+    /// Location of an artifact the job produced, such as an uploaded export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Hash of the transaction the job sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+}
+
+/// Arguments of the log export job
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct LogExportRequest {
+    /// Object name below the rollup's `logs/` S3 prefix; defaults to a timestamped name
+    #[serde(default)]
+    pub destination: Option<String>,
+    /// Also export the captured script output of each deployment step
+    #[serde(default)]
+    pub include_step_outputs: bool,
+}
+
+/// Outcome of reloading the operator config and rollup metadata
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ConfigReload {
+    /// Names of the settings that changed; secret values are never included
+    pub changed: Vec<String>,
+    /// Whether a changed setting only takes effect once the containers are restarted
+    pub restart_required: bool,
 }
 
 /// Public metadata about the rollup - contains no private keys
@@ -21,8 +481,8 @@ pub struct RollupMetadata {
     /// Rollup name
     pub name: String,
     /// Rollup chain ID
-    pub chain_id: u64,
-    /// Avail app ID
+    pub chain_id: ChainId,
+    /// Avail app ID (must parse as an [`AvailAppId`])
     pub avail_app_id: String,
     /// Parent chain RPC endpoint (public endpoint)
     pub parent_chain_rpc: String,
@@ -32,4 +492,438 @@ pub struct RollupMetadata {
     pub local_rpc_endpoint: String,
     /// Explorer URL
     pub explorer_url: String,
+    /// Operator-defined labels for fleet management, e.g. team or environment
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+impl RollupMetadata {
+    /// Get a copy with every URL reduced to its origin by [`redact_url`]
+    pub fn redacted(&self) -> Self {
+        Self {
+            parent_chain_rpc: redact_url(&self.parent_chain_rpc),
+            local_rpc_endpoint: redact_url(&self.local_rpc_endpoint),
+            explorer_url: redact_url(&self.explorer_url),
+            ..self.clone()
+        }
+    }
+
+    /// Parse and validate the Avail app ID
+    pub fn parse_avail_app_id(&self) -> Result<AvailAppId, String> {
+        self.avail_app_id.parse()
+    }
+
+    /// Check that `update` leaves the fields fixed at deployment unchanged
+    ///
+    /// The chain ID, Avail app ID, parent chain RPC and S3 fallback setting are baked into
+    /// the running node, so changing them here would only corrupt its recorded view.
+    pub fn check_immutable_fields(&self, update: &RollupMetadata) -> Result<(), String> {
+        let mut changed = Vec::new();
+        if self.chain_id != update.chain_id {
+            changed.push(format!(
+                "chain_id ({} -> {})",
+                self.chain_id, update.chain_id
+            ));
+        }
+        if self.parse_avail_app_id().ok() != update.parse_avail_app_id().ok() {
+            changed.push(format!(
+                "avail_app_id ({} -> {})",
+                self.avail_app_id, update.avail_app_id
+            ));
+        }
+        if self.parent_chain_rpc != update.parent_chain_rpc {
+            changed.push("parent_chain_rpc".to_string());
+        }
+        if self.fallback_s3_enable != update.fallback_s3_enable {
+            changed.push(format!(
+                "fallback_s3_enable ({} -> {})",
+                self.fallback_s3_enable, update.fallback_s3_enable
+            ));
+        }
+
+        if changed.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Cannot change immutable fields of a deployed rollup: {}",
+                changed.join(", ")
+            ))
+        }
+    }
+
+    /// Apply the fields of `update` that may change after deployment
+    pub fn apply_mutable_fields(&mut self, update: &RollupMetadata) {
+        self.name = update.name.clone();
+        self.local_rpc_endpoint = update.local_rpc_endpoint.clone();
+        self.explorer_url = update.explorer_url.clone();
+        self.labels = update.labels.clone();
+    }
+
+    /// Trim whitespace and trailing slashes from the rollup's RPC endpoint and explorer URL
+    pub fn normalize_urls(&mut self) {
+        for url in [&mut self.local_rpc_endpoint, &mut self.explorer_url] {
+            *url = url.trim().trim_end_matches('/').to_string();
+        }
+    }
+
+    /// Check that the RPC endpoint and explorer URL are valid URLs
+    ///
+    /// The RPC endpoint may use HTTP(S) or WebSocket, while the explorer must be a web page.
+    pub fn validate_urls(&self) -> Result<(), String> {
+        validate_url(&self.local_rpc_endpoint)
+            .map_err(|e| format!("Invalid local RPC endpoint: {}", e))?;
+        validate_url(&self.explorer_url).map_err(|e| format!("Invalid explorer URL: {}", e))?;
+        if !self.explorer_url.starts_with("http://") && !self.explorer_url.starts_with("https://") {
+            return Err(format!(
+                "Invalid explorer URL: '{}' must use http or https",
+                self.explorer_url
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that no label has an empty key or value
+    pub fn validate_labels(&self) -> Result<(), String> {
+        for (key, value) in &self.labels {
+            if key.trim().is_empty() {
+                return Err("Label keys must be non-empty".to_string());
+            }
+            if value.trim().is_empty() {
+                return Err(format!("Label '{}' has an empty value", key));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse labels written as `key=value` pairs separated by commas, e.g. `team=infra,env=prod`
+pub fn parse_labels(s: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut labels = BTreeMap::new();
+    for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid label '{}', expected key=value", pair))?;
+        let (key, value) = (key.trim(), value.trim());
+        if key.is_empty() || value.is_empty() {
+            return Err(format!(
+                "Invalid label '{}', key and value must be non-empty",
+                pair
+            ));
+        }
+        labels.insert(key.to_string(), value.to_string());
+    }
+    Ok(labels)
+}
+
+/// EVM chain ID of the rollup
+///
+/// Chain IDs are non-zero and at most the largest value that EIP-2294 allows. Serialized
+/// as a plain `u64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u64", into = "u64")]
+pub struct ChainId(u64);
+
+impl ChainId {
+    /// Chain ID used by the orbit setup tooling when none is configured
+    pub const DEFAULT_ORBIT: ChainId = ChainId(412346);
+
+    /// Largest chain ID allowed by EIP-2294
+    pub const MAX: u64 = u64::MAX / 2 - 36;
+
+    /// Well-known public chains a rollup must not reuse the chain ID of
+    const WELL_KNOWN: &'static [(u64, &'static str)] = &[
+        (1, "Ethereum Mainnet"),
+        (10, "OP Mainnet"),
+        (56, "BNB Smart Chain"),
+        (137, "Polygon PoS"),
+        (8453, "Base"),
+        (17000, "Holesky"),
+        (42161, "Arbitrum One"),
+        (42170, "Arbitrum Nova"),
+        (421614, "Arbitrum Sepolia"),
+        (11155111, "Sepolia"),
+    ];
+
+    /// Create a new chain ID, rejecting zero and values above [`ChainId::MAX`]
+    pub fn new(id: u64) -> Result<Self, String> {
+        if id == 0 {
+            return Err("Chain ID must be non-zero".to_string());
+        }
+        if id > Self::MAX {
+            return Err(format!(
+                "Chain ID {} exceeds the maximum of {}",
+                id,
+                Self::MAX
+            ));
+        }
+        Ok(Self(id))
+    }
+
+    /// Get the raw chain ID
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+
+    /// Name of the well-known public chain using this ID, if any
+    pub fn well_known_name(&self) -> Option<&'static str> {
+        Self::WELL_KNOWN
+            .iter()
+            .find(|(id, _)| *id == self.0)
+            .map(|(_, name)| *name)
+    }
+
+    /// Fail if the ID collides with a well-known public chain
+    pub fn check_collision(&self) -> Result<(), String> {
+        match self.well_known_name() {
+            Some(name) => Err(format!("Chain ID {} is already used by {}", self.0, name)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for ChainId {
+    fn default() -> Self {
+        Self::DEFAULT_ORBIT
+    }
+}
+
+impl TryFrom<u64> for ChainId {
+    type Error = String;
+
+    fn try_from(id: u64) -> Result<Self, Self::Error> {
+        Self::new(id)
+    }
+}
+
+impl From<ChainId> for u64 {
+    fn from(id: ChainId) -> Self {
+        id.0
+    }
+}
+
+impl FromStr for ChainId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let id = s
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid chain ID '{}': {}", s, e))?;
+        Self::new(id)
+    }
+}
+
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Avail application ID
+///
+/// Avail app IDs are non-zero `u32` values. [`RollupMetadata`] carries the ID as a string
+/// so it can be passed through job arguments; this type is used wherever it is consumed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct AvailAppId(u32);
+
+impl AvailAppId {
+    /// Create a new app ID, rejecting zero
+    pub fn new(id: u32) -> Result<Self, String> {
+        if id == 0 {
+            return Err("Avail app ID must be non-zero".to_string());
+        }
+        Ok(Self(id))
+    }
+
+    /// Get the raw app ID
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+impl TryFrom<u32> for AvailAppId {
+    type Error = String;
+
+    fn try_from(id: u32) -> Result<Self, Self::Error> {
+        Self::new(id)
+    }
+}
+
+impl From<AvailAppId> for u32 {
+    fn from(id: AvailAppId) -> Self {
+        id.0
+    }
+}
+
+impl FromStr for AvailAppId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Avail app ID is empty".to_string());
+        }
+
+        let id = s
+            .parse::<u32>()
+            .map_err(|e| format!("Invalid Avail app ID '{}': {}", s, e))?;
+        Self::new(id)
+    }
+}
+
+impl fmt::Display for AvailAppId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata_with_urls(rpc: &str, explorer: &str) -> RollupMetadata {
+        RollupMetadata {
+            local_rpc_endpoint: rpc.to_string(),
+            explorer_url: explorer.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validate_urls_accepts_valid_endpoints() {
+        metadata_with_urls("http://localhost:8547", "https://explorer.example.com")
+            .validate_urls()
+            .unwrap();
+        metadata_with_urls("wss://rpc.example.com", "http://localhost:4000")
+            .validate_urls()
+            .unwrap();
+    }
+
+    #[test]
+    fn validate_urls_rejects_malformed_endpoints() {
+        let err = metadata_with_urls("localhost:8547", "https://explorer.example.com")
+            .validate_urls()
+            .unwrap_err();
+        assert!(err.starts_with("Invalid local RPC endpoint"));
+
+        let err = metadata_with_urls("http://localhost:8547", "explorer")
+            .validate_urls()
+            .unwrap_err();
+        assert!(err.starts_with("Invalid explorer URL"));
+
+        let err = metadata_with_urls("http://localhost:8547", "ws://explorer.example.com")
+            .validate_urls()
+            .unwrap_err();
+        assert!(err.contains("must use http or https"));
+    }
+
+    #[test]
+    fn normalize_urls_trims_whitespace_and_trailing_slashes() {
+        let mut metadata = metadata_with_urls(" http://localhost:8547/ ", "https://explorer.io//");
+        metadata.normalize_urls();
+        assert_eq!(metadata.local_rpc_endpoint, "http://localhost:8547");
+        assert_eq!(metadata.explorer_url, "https://explorer.io");
+    }
+
+    #[test]
+    fn chain_id_rejects_well_known_chains() {
+        for id in [1, 10, 42161, 421614, 11155111] {
+            let err = ChainId::new(id).unwrap().check_collision().unwrap_err();
+            assert!(err.starts_with(&format!("Chain ID {} is already used by", id)));
+        }
+        ChainId::DEFAULT_ORBIT.check_collision().unwrap();
+        ChainId::new(987654).unwrap().check_collision().unwrap();
+    }
+
+    #[test]
+    fn chain_id_parsing_rejects_invalid_values() {
+        assert_eq!("412346".parse::<ChainId>().unwrap(), ChainId::DEFAULT_ORBIT);
+        for value in ["", "abc", "-1", "0", &(ChainId::MAX + 1).to_string()] {
+            assert!(
+                value.parse::<ChainId>().is_err(),
+                "{} should be invalid",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn avail_app_id_parses_non_zero_integers() {
+        assert_eq!("42".parse::<AvailAppId>().unwrap().get(), 42);
+        assert_eq!(" 7 ".parse::<AvailAppId>().unwrap().get(), 7);
+        assert_eq!(serde_json::from_str::<AvailAppId>("9").unwrap().get(), 9);
+    }
+
+    #[test]
+    fn avail_app_id_rejects_empty_non_numeric_and_zero() {
+        assert_eq!(
+            "".parse::<AvailAppId>().unwrap_err(),
+            "Avail app ID is empty"
+        );
+        assert_eq!(
+            "   ".parse::<AvailAppId>().unwrap_err(),
+            "Avail app ID is empty"
+        );
+        for value in ["abc", "1.5", "-3", "0x10", "4294967296"] {
+            let err = value.parse::<AvailAppId>().unwrap_err();
+            assert!(
+                err.starts_with("Invalid Avail app ID"),
+                "{}: {}",
+                value,
+                err
+            );
+        }
+        assert_eq!(
+            "0".parse::<AvailAppId>().unwrap_err(),
+            "Avail app ID must be non-zero"
+        );
+        assert!(AvailAppId::try_from(0).is_err());
+        assert!(serde_json::from_str::<AvailAppId>("0").is_err());
+    }
+
+    #[test]
+    fn push_log_keeps_the_most_recent_lines() {
+        let mut status = DeploymentStatus::default();
+        for i in 0..MAX_STATUS_LOG_LINES + 10 {
+            status.push_log(format!("line {}", i));
+        }
+        assert_eq!(status.logs.len(), MAX_STATUS_LOG_LINES);
+        assert_eq!(status.logs[0], "line 10");
+
+        status.push_log("é".repeat(MAX_STATUS_LOG_LINE_BYTES));
+        let last = status.logs.last().unwrap();
+        assert!(last.ends_with(" [truncated]"));
+        assert!(last.len() <= MAX_STATUS_LOG_LINE_BYTES + " [truncated]".len());
+    }
+
+    #[test]
+    fn stopped_rollup_is_not_interrupted_after_reload() {
+        let mut status = DeploymentStatus {
+            deployed: true,
+            healthy: true,
+            metadata: Some(RollupMetadata::default()),
+            container_ids: vec!["abc123".to_string()],
+            completed_steps: DeploymentStep::all().to_vec(),
+            phase: DeploymentPhase::Complete,
+            ..Default::default()
+        };
+        status.mark_stopped();
+
+        let path = std::env::temp_dir().join(format!(
+            "avail-orbit-raas-stopped-status-{}.json",
+            std::process::id()
+        ));
+        status.save_to_path(&path).unwrap();
+        let mut reloaded = DeploymentStatus::load_from_path(&path).unwrap().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(!reloaded.is_in_progress());
+        assert!(!reloaded.mark_interrupted());
+        assert_eq!(reloaded.phase, DeploymentPhase::Stopped);
+        assert!(!reloaded.deployed);
+        assert!(reloaded.failure.is_none());
+        assert_eq!(reloaded.container_ids, ["abc123"]);
+        assert!(reloaded.metadata.is_some());
+    }
 }